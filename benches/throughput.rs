@@ -0,0 +1,50 @@
+//! Command throughput against the in-memory camera, for sizing the command interval and
+//! for spotting overhead added to the command path. Measures tiny-ptz's own cost per
+//! move (building arguments, bookkeeping, the executor call), not a real camera's.
+//!
+//! `cargo bench --features testing`; pass a number to change the burst size.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tiny_ptz::camera::{CameraConfig, CameraController};
+use tiny_ptz::fake::FakeCamera;
+
+const DEFAULT_BURST: u32 = 20_000;
+
+fn report(name: &str, calls: u32, elapsed: Duration) {
+    let per_call = elapsed / calls.max(1);
+    let per_second = calls as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+    println!("{:<10} {:>7} calls in {:>9.2?}  {:>10.0} moves/s  {:>9.2?}/call", name, calls, elapsed, per_second, per_call);
+}
+
+/// A controller on a fresh fake camera, without zoom adjustment so every step is the same
+fn controller() -> CameraController {
+    let mut config = CameraConfig::default();
+    config.movement.zoom_adjust = false;
+    let mut controller = CameraController::with_executor(config, Arc::new(FakeCamera::new()));
+    controller.sync_from_device().expect("the fake camera answers");
+    controller
+}
+
+fn main() {
+    // `cargo bench` passes `--bench`; the first numeric argument, if any, is the burst size
+    let burst = std::env::args().skip(1).find_map(|arg| arg.parse().ok()).unwrap_or(DEFAULT_BURST);
+
+    // Alternate direction so every call is a real change and gets sent
+    let mut single = controller();
+    let start = Instant::now();
+    for i in 0..burst {
+        single.set_pan(if i % 2 == 0 { 1 } else { -1 }).expect("set_pan");
+    }
+    report("set_pan", burst, start.elapsed());
+
+    // All three axes in one `--set-ctrl a=1,b=2,c=3` call
+    let mut batched = controller();
+    let start = Instant::now();
+    for i in 0..burst {
+        let offset = (i % 2) as i32;
+        batched.move_to(offset * 3600, offset * 3600, offset * 10).expect("move_to");
+    }
+    report("move_to", burst, start.elapsed());
+}
@@ -1,118 +1,1676 @@
-use crate::camera::{CameraController, CameraConfig};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use std::time::{Duration, Instant};
-use std::process::Command;
+use crate::backend::{CtlOutput, Executor, V4l2Ctl};
+use crate::camera::{Axis, CameraController, CameraConfig, ControlConfig, MotionProfile, MoveOutcome, SmoothMove, VideoConfig};
+use crate::keys::{Action, KeyBindings};
+use crate::looks::{self, LOOKS_DIR};
+use crate::presets::{self, Preset, PresetsStore, PRESETS_FILE, SYSTEM_PRESETS_FILE};
+use crate::state::{self, State, STATE_FILE};
+use crate::theme::{Theme, THEME_NAMES};
+use crate::ui;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position as CellPosition, Rect};
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{mpsc, Arc};
+use std::thread;
 
 pub enum InputEvent {
     Key(KeyEvent),
+    /// Scroll-wheel and click events, when `ui.mouse` is on
+    Mouse(MouseEvent),
     Tick,
+    /// SIGINT/SIGTERM: quit through the normal cleanup path
+    Shutdown,
+}
+
+/// Where the app keeps its files. The defaults are relative to the working directory.
+#[derive(Debug, Clone)]
+pub struct AppPaths {
+    /// Personal bookmarks, unless the config has `[presets]`
+    pub presets: PathBuf,
+    /// Presets shared by every user, read but never written
+    pub system_presets: PathBuf,
+    /// Preferences such as the theme
+    pub state: PathBuf,
+}
+
+impl Default for AppPaths {
+    fn default() -> Self {
+        AppPaths {
+            presets: PathBuf::from(PRESETS_FILE),
+            system_presets: PathBuf::from(SYSTEM_PRESETS_FILE),
+            state: PathBuf::from(STATE_FILE),
+        }
+    }
+}
+
+#[cfg(test)]
+impl AppPaths {
+    /// Fresh, not yet existing files in the temp directory, so tests neither read nor
+    /// write the working directory's
+    pub fn scratch() -> Self {
+        static NEXT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let file = |name: &str| std::env::temp_dir().join(format!("tiny-ptz-{}-{}-{}", std::process::id(), n, name));
+        AppPaths { presets: file(PRESETS_FILE), system_presets: file("system-presets.toml"), state: file(STATE_FILE) }
+    }
 }
 
 pub struct App {
     pub camera_controller: CameraController, // Make this field public
     pub should_quit: bool,
     pub status_message: String,
+    /// Show the debug strip (`--verbose`)
+    pub verbose: bool,
+    /// Text-only display with spelled-out movement feedback (`--plain`)
+    pub plain: bool,
     last_command_time: Instant,
+    last_input_time: Instant,
+    last_user_command_time: Instant,
     command_interval: Duration,
-    video_feed_pid: Option<u32>,
+    video_feed: Option<Child>,
+    /// A player asked to exit, and when it gets killed if it hasn't
+    stopping_player: Option<(Child, Instant)>,
+    /// Attempt number of the running player and when it was launched
+    video_launch: Option<(u32, Instant)>,
+    /// A preview relaunch scheduled after a busy device: when, and which attempt it will be
+    video_retry: Option<(Instant, u32)>,
+    video_profiles: Vec<String>,
+    video_profile: usize,
+    preview_idle_stopped: bool,
+    /// Whether the capture device node was there at the last check; `None` until it has
+    /// been seen, so a camera missing at startup isn't reported as reconnected when it shows up
+    device_present: Option<bool>,
+    /// A preview was running when the capture device disappeared
+    preview_was_running: bool,
+    bookmarks: Vec<Preset>,
+    bookmark_index: Option<usize>,
+    /// The status message when it reports a failure, as set by `set_error`; any other
+    /// status replaces it
+    error_status: Option<String>,
+    /// Bookmarks were added since startup, so they are written on quit
+    bookmarks_changed: bool,
+    /// The personal presets failed to load at startup, so saving them is refused
+    presets_load_failed: bool,
+    /// Where personal bookmarks are written: `paths.presets`, or the config's `[presets]`
+    presets_store: PresetsStore,
+    /// The presets list, while it's open: the selected row
+    presets_panel: Option<usize>,
+    /// With `confirm_preset_overwrite`: the slot whose overwrite awaits a second Enter, and since when
+    pending_overwrite: Option<(usize, Instant)>,
+    /// Settings from outside any `[profile.*]`, restored when switching back to the base
+    base_motion: MotionProfile,
+    /// The active `[profile.<name>]`, if any
+    motion_profile: Option<String>,
+    /// Name of the look last applied, so the look key moves on to the next one
+    last_look: Option<String>,
+    /// Preferences persisted to `paths.state`
+    state: State,
+    state_changed: bool,
+    /// False after `QuitWithoutSaving`: `cleanup` then writes nothing
+    save_on_quit: bool,
+    paths: AppPaths,
+    /// The active theme, one of `THEME_NAMES` applied
+    theme: Theme,
+    /// Per axis (in `Axis::ALL` order): which way the control value last moved, and when
+    active_motion: [Option<(i32, Instant)>; 3],
+    /// Per axis: the latest step move that arrived within the command interval, sent with
+    /// the others once it elapses (`movement.queue_moves`)
+    pending_moves: [Option<Action>; 3],
+    /// Per axis: the value `--plain` feedback last reported, to describe the next change
+    announced: [i32; 3],
+    /// With `movement.debounce_ms`: the last move staged but not yet sent, and when
+    last_staged: Option<(Action, Instant)>,
+    /// With `movement.smooth_move_ms`: the glide to a recalled bookmark in progress
+    smooth_move: Option<SmoothMove>,
+    /// With `verify_presets`: the bookmark a glide in progress is recalling, checked on arrival
+    verify_on_arrival: Option<usize>,
+    /// Set with the lock key: camera commands from keys and the mouse are refused
+    locked: bool,
+    /// Set with the arrow-zoom key: the tilt keys zoom instead, for keyboards where Shift is a stretch
+    arrows_zoom: bool,
+    /// The last step or nudge move dispatched, for the repeat key
+    last_move: Option<Action>,
+    /// The `[zoom] stops` entry last zoomed to with the quick-zoom key
+    zoom_stop_index: Option<usize>,
+    /// What the last single-axis move did, for the status message once it has finished
+    move_outcome: Option<(Axis, MoveOutcome)>,
+    /// Where the camera was before the last jump or bookmark recall, for Return to previous
+    previous_position: Option<[i32; 3]>,
+    /// Where the Pan, Tilt and Zoom panels were last drawn, for routing mouse events;
+    /// empty for a panel that isn't shown. Set by `ui::render`, which only gets `&App`.
+    panel_areas: Cell<[Rect; 3]>,
+    /// The last `STATUS_HISTORY_LEN` status messages and when each appeared, for `--session-log`
+    status_history: VecDeque<(SystemTime, String)>,
+    #[cfg(feature = "clipboard")]
+    clipboard: Option<arboard::Clipboard>,
+    /// Results of `[hooks]` commands, sent back by the threads running them
+    hook_results: (mpsc::Sender<HookResult>, mpsc::Receiver<HookResult>),
+}
+
+/// A finished `[hooks]` command and what it produced
+type HookResult = (String, std::io::Result<CtlOutput>);
+
+/// Launch attempts for the preview while the device is busy, and the wait before the
+/// first retry, doubled for each one after it
+const PREVIEW_LAUNCH_ATTEMPTS: u32 = 3;
+const PREVIEW_RETRY_BACKOFF: Duration = Duration::from_millis(250);
+/// A player that fails this soon after launch failed to open the device, rather than
+/// being closed by the user or losing the device mid-stream
+const PREVIEW_LAUNCH_GRACE: Duration = Duration::from_secs(2);
+
+/// Where the player's stderr goes, so a failed launch can be diagnosed
+fn video_feed_log_path() -> std::path::PathBuf {
+    std::env::temp_dir().join("tiny-ptz-ffplay.log")
+}
+
+/// How long a direction arrow stays lit after a move, on top of the command interval,
+/// so it stays on steadily while a key is held and auto-repeats
+const MOTION_INDICATOR_HOLD: Duration = Duration::from_millis(200);
+
+/// Minimum time between commands unless a motion profile sets one
+const DEFAULT_COMMAND_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long a first Enter on an occupied preset stays armed under `confirm_preset_overwrite`
+const OVERWRITE_CONFIRM_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Status messages kept for `--session-log`; older ones are dropped
+const STATUS_HISTORY_LEN: usize = 200;
+
+/// Bounds and increment for adjusting `command_interval` with `[` / `]`
+const COMMAND_INTERVAL_MIN: Duration = Duration::from_millis(25);
+const COMMAND_INTERVAL_MAX: Duration = Duration::from_millis(1000);
+const COMMAND_INTERVAL_STEP: Duration = Duration::from_millis(25);
+
+/// Status for a single-axis move that didn't simply go out as asked, or `None` if it did
+fn describe_outcome(axis: Axis, outcome: MoveOutcome, control: &ControlConfig) -> Option<String> {
+    let name = axis.name();
+    let label = format!("{}{}", name[..1].to_uppercase(), &name[1..]);
+    let at_limit = outcome.achieved == control.min || outcome.achieved == control.max;
+    // Where the move was headed within the limits; short of it unsent means the deadband held it back
+    let target = outcome.requested.clamp(control.min, control.max);
+    match outcome {
+        MoveOutcome { command_sent: false, achieved, .. } if target != achieved => {
+            Some(format!("{} move of {} is within its deadband; nothing sent.", label, target - achieved))
+        }
+        MoveOutcome { command_sent: false, clamped: true, achieved, .. } if at_limit => {
+            Some(format!("{} is at its limit ({}); nothing sent.", label, achieved))
+        }
+        MoveOutcome { command_sent: false, achieved, .. } => Some(format!("{} is already at {}; nothing sent.", label, achieved)),
+        MoveOutcome { clamped: true, achieved, .. } if at_limit => Some(format!("Command sent; {} stopped at its limit ({}).", name, achieved)),
+        MoveOutcome { .. } => None,
+    }
+}
+
+/// Whether a v4l2-ctl failure was caused by the device being held open elsewhere (EBUSY)
+fn is_busy_error(error: &anyhow::Error) -> bool {
+    error.to_string().to_lowercase().contains("busy")
+}
+
+/// Writes BEL characters straight to the terminal; failures are not worth reporting
+fn ring_bell(times: usize) {
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all("\x07".repeat(times).as_bytes());
+    let _ = stdout.flush();
+}
+
+/// Formats a time as `2026-10-14 09:30:05 UTC`, without pulling in a date crate
+fn format_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+    let (days, rem) = ((secs / 86400) as i64, secs % 86400);
+    // Civil date from days since 1970-01-01, after Howard Hinnant's `civil_from_days`
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+/// Writes `text` to a temp file for systems without a clipboard, returning the path
+fn write_fallback_clipboard(text: &str) -> anyhow::Result<String> {
+    let path = std::env::temp_dir().join("tiny-ptz-position.txt");
+    std::fs::write(&path, format!("{}\n", text))?;
+    Ok(path.display().to_string())
 }
 
 impl App {
     pub fn new(config: CameraConfig) -> Self {
-        App {
-            camera_controller: CameraController::new(config),
+        Self::with_executor(config, Arc::new(V4l2Ctl))
+    }
+
+    /// An app whose camera commands go through `executor` instead of `v4l2-ctl`
+    pub fn with_executor(config: CameraConfig, executor: Arc<dyn Executor>) -> Self {
+        Self::with_paths(config, executor, AppPaths::default())
+    }
+
+    /// Like `with_executor`, keeping bookmarks and preferences at `paths`
+    pub fn with_paths(mut config: CameraConfig, executor: Arc<dyn Executor>, paths: AppPaths) -> Self {
+        if config.video.profile.is_empty() {
+            config.video.profile = VideoConfig::default().profile;
+        }
+        config.release_hooked_keys();
+        if !config.movement.shift_up_zooms_in {
+            config.keys.swap_default_zoom_keys();
+        }
+        let video_profiles: Vec<String> = config.video.profile.keys().cloned().collect();
+        let video_profile = config.video.default_profile.as_ref()
+            .and_then(|name| video_profiles.iter().position(|p| p == name))
+            .unwrap_or(0);
+        let mut status_message = match config.keys.key_for(Action::Quit) {
+            Some(quit) => format!("Press '{}' to quit. See Help for all keys.", quit),
+            None => "See Help for all keys.".to_string(),
+        };
+        let mut status_failed = false;
+        // An inline `[presets]` section takes the place of presets.toml
+        let personal = match config.inline_presets() {
+            Some(inline) => {
+                if paths.presets.exists() {
+                    status_message = format!("Using [presets] from the config; {} is ignored.", paths.presets.display());
+                }
+                Ok(inline)
+            }
+            None => presets::load_bookmarks(&paths.presets),
+        };
+        // Loaded apart so a broken system file only costs the system presets. Without the
+        // personal ones, saving would replace them with whatever this session adds.
+        let mut presets_load_failed = false;
+        let personal = personal.unwrap_or_else(|e| {
+            status_message = format!("Bookmarks not loaded: {:#}; presets won't be saved this session", e);
+            status_failed = true;
+            presets_load_failed = true;
+            Vec::new()
+        });
+        let system = presets::load_bookmarks(&paths.system_presets).unwrap_or_else(|e| {
+            status_message = format!("System presets not loaded: {:#}", e);
+            status_failed = true;
+            Vec::new()
+        });
+        let bookmarks = presets::with_system_presets(system, personal);
+        let state = state::load_state(&paths.state).unwrap_or_else(|e| {
+            status_message = format!("UI state not loaded: {:#}", e);
+            status_failed = true;
+            State::default()
+        });
+        let theme = state
+            .theme
+            .as_deref()
+            .and_then(|name| Theme::named(name, &config.ui.theme))
+            .unwrap_or_else(|| config.ui.theme.clone());
+        let base_motion = MotionProfile::capture(&config, DEFAULT_COMMAND_INTERVAL.as_millis() as u64);
+        let motion_profile = config.motion_profile.clone();
+        let mut camera_controller = CameraController::with_executor(config, executor);
+        // A failure here means v4l2-ctl itself is unusable, which the sync below reports
+        if let Ok(unsupported) = camera_controller.detect_unsupported_axes() {
+            if !unsupported.is_empty() {
+                let names = unsupported.iter().map(|axis| axis.name()).collect::<Vec<_>>().join(" and ");
+                let (first, rest) = names.split_at(1);
+                status_message = format!("{}{} not supported on this camera; its keys are disabled.", first.to_uppercase(), rest);
+                status_failed = false;
+            }
+        }
+        if let Err(e) = camera_controller.sync_from_device() {
+            status_message = format!("Could not read the camera's position, assuming defaults: {:#}", e);
+            status_failed = true;
+        }
+        match camera_controller.apply_startup_position() {
+            Ok(true) => {
+                status_message = "Moved to the startup position.".to_string();
+                status_failed = false;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                status_message = format!("Failed to move to the startup position: {:#}", e);
+                status_failed = true;
+            }
+        }
+        let error_status = status_failed.then(|| status_message.clone());
+        let device_present = Path::new(camera_controller.config.capture_device()).exists().then_some(true);
+        let mut app = App {
+            camera_controller,
             should_quit: false,
-            status_message: "Press 'q' to quit. Arrow keys for Pan/Tilt. Shift+Arrows for Zoom. 'v' for video feed.".to_string(),
+            status_message,
+            verbose: false,
+            plain: false,
             last_command_time: Instant::now(),
-            command_interval: Duration::from_millis(100),
-            video_feed_pid: None,
+            last_input_time: Instant::now(),
+            last_user_command_time: Instant::now(),
+            command_interval: DEFAULT_COMMAND_INTERVAL,
+            video_feed: None,
+            stopping_player: None,
+            video_launch: None,
+            video_retry: None,
+            video_profiles,
+            video_profile,
+            preview_idle_stopped: false,
+            device_present,
+            preview_was_running: false,
+            bookmarks,
+            bookmark_index: None,
+            error_status,
+            bookmarks_changed: false,
+            presets_load_failed,
+            presets_store: PresetsStore::File(paths.presets.clone()),
+            presets_panel: None,
+            pending_overwrite: None,
+            base_motion,
+            motion_profile: None,
+            last_look: None,
+            state,
+            state_changed: false,
+            save_on_quit: true,
+            paths,
+            theme,
+            active_motion: [None; 3],
+            pending_moves: [None; 3],
+            announced: [0; 3],
+            last_staged: None,
+            smooth_move: None,
+            verify_on_arrival: None,
+            locked: false,
+            arrows_zoom: false,
+            last_move: None,
+            zoom_stop_index: None,
+            move_outcome: None,
+            previous_position: None,
+            panel_areas: Cell::new([Rect::default(); 3]),
+            status_history: VecDeque::new(),
+            #[cfg(feature = "clipboard")]
+            clipboard: None,
+            hook_results: mpsc::channel(),
+        };
+        app.select_motion_profile(motion_profile);
+        app.announced = Axis::ALL.map(|axis| app.camera_controller.position_of(axis));
+        app.record_status();
+        app
+    }
+
+    /// Shows `message` as the status, marked as a failure for `status_is_error`
+    pub fn set_error(&mut self, message: String) {
+        self.error_status = Some(message.clone());
+        self.status_message = message;
+    }
+
+    /// Whether the current status reports a failure, i.e. was set with `set_error`
+    pub fn status_is_error(&self) -> bool {
+        self.error_status.as_deref() == Some(self.status_message.as_str())
+    }
+
+    /// Adds the status message to the history if it changed since the last one recorded
+    fn record_status(&mut self) {
+        if self.status_history.back().is_some_and(|(_, message)| *message == self.status_message) {
+            return;
         }
+        if self.status_history.len() == STATUS_HISTORY_LEN {
+            self.status_history.pop_front();
+        }
+        self.status_history.push_back((SystemTime::now(), self.status_message.clone()));
+    }
+
+    /// Recent status messages, oldest first, with the time each appeared
+    pub fn status_history(&self) -> impl Iterator<Item = &(SystemTime, String)> {
+        self.status_history.iter()
+    }
+
+    /// Saves bookmarks back into the `[presets]` section of `config_path` when the config
+    /// has one, rather than to `presets.toml`
+    pub fn use_config_presets(&mut self, config_path: &Path) {
+        if self.camera_controller.config.presets.is_some() {
+            self.presets_store = PresetsStore::Config(config_path.to_path_buf());
+        }
+    }
+
+    /// Appends the status history to `path` under a header naming the config and device
+    pub fn write_session_log(&self, path: &Path, config_path: &Path) -> anyhow::Result<()> {
+        use anyhow::Context;
+        let config_path = std::fs::canonicalize(config_path).unwrap_or_else(|_| config_path.to_path_buf());
+        let mut log = format!(
+            "== tiny-ptz session ended {} ==\nconfig: {}\ndevice: {}\n",
+            format_timestamp(SystemTime::now()),
+            config_path.display(),
+            self.camera_controller.config.device
+        );
+        for (time, message) in &self.status_history {
+            log.push_str(&format!("{}  {}\n", format_timestamp(*time), message));
+        }
+        log.push('\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(log.as_bytes()))
+            .with_context(|| format!("Failed to write the session log to {}", path.display()))
+    }
+
+    /// Restores the base movement settings and overlays the named `[profile.<name>]` on them
+    fn select_motion_profile(&mut self, name: Option<String>) {
+        let config = &mut self.camera_controller.config;
+        self.base_motion.restore(config);
+        let mut interval_ms = self.base_motion.command_interval_ms;
+        if let Some(profile) = name.as_ref().and_then(|name| config.profile.get(name)).cloned() {
+            profile.apply(config);
+            interval_ms = profile.command_interval_ms.or(interval_ms);
+        }
+        if let Some(ms) = interval_ms {
+            self.command_interval = Duration::from_millis(ms);
+        }
+        self.motion_profile = name;
+    }
+
+    /// Switches to the next `[profile.*]` by name, then back to the base settings
+    fn cycle_motion_profile(&mut self) {
+        let names = self.camera_controller.config.profile_names();
+        if names.is_empty() {
+            self.status_message = "No motion profiles configured; add [profile.<name>] sections.".to_string();
+            return;
+        }
+        let next = match &self.motion_profile {
+            None => names.first().cloned(),
+            Some(current) => names.iter().skip_while(|name| *name != current).nth(1).cloned(),
+        };
+        self.status_message = match &next {
+            Some(name) => format!("Motion profile: {}", name),
+            None => "Motion profile: base settings".to_string(),
+        };
+        self.select_motion_profile(next);
     }
 
     fn toggle_video_feed(&mut self) {
-        if let Some(pid) = self.video_feed_pid.take() {
-            // Video feed is running, kill it
+        if self.video_retry.take().is_some() {
+            self.status_message = "Video feed retry cancelled.".to_string();
+        } else if self.video_feed.is_some() {
+            self.stop_video_feed();
+            self.status_message = "Video feed stopped.".to_string();
+        } else {
+            self.start_video_feed();
+        }
+    }
+
+    /// Asks the player to exit. It gets `video.cleanup_timeout_ms` to do so before
+    /// `reap_stopping_player` kills it outright, without holding up the UI meanwhile.
+    fn stop_video_feed(&mut self) {
+        self.video_launch = None;
+        if let Some(child) = self.video_feed.take() {
+            // SIGTERM first so the player can release the device cleanly
             let _ = Command::new("kill")
-                .arg(pid.to_string())
+                .arg(child.id().to_string())
                 .output();
-            self.status_message = "Video feed stopped.".to_string();
+            let timeout = Duration::from_millis(self.camera_controller.config.video.cleanup_timeout_ms);
+            if let Some((mut previous, _)) = self.stopping_player.replace((child, Instant::now() + timeout)) {
+                let _ = previous.kill();
+                let _ = previous.wait();
+            }
+        }
+        self.camera_controller.set_preview_active(false);
+    }
+
+    /// Reaps a player asked to exit by `stop_video_feed`, killing it once its time is up
+    fn reap_stopping_player(&mut self, now: Instant) {
+        let Some((child, deadline)) = self.stopping_player.as_mut() else {
+            return;
+        };
+        let running = matches!(child.try_wait(), Ok(None));
+        if running && now < *deadline {
+            return;
+        }
+        if running {
+            let _ = child.kill(); // SIGKILL
+            let _ = child.wait();
+        }
+        self.stopping_player = None;
+    }
+
+    fn start_video_feed(&mut self) {
+        self.video_retry = None;
+        if self.stopping_player.is_some() {
+            // The old player may still hold the device; `check_video_feed_retry` launches once it's gone
+            self.video_retry = Some((Instant::now(), 1));
+            self.status_message = "Video feed starts once the previous player has exited.".to_string();
+            return;
+        }
+        self.launch_video_feed(1);
+    }
+
+    /// Starts the player in the background. A missing player is reported straight away;
+    /// other failures are retried with backoff, as are players that exit at once on a busy device.
+    fn launch_video_feed(&mut self, attempt: u32) {
+        let device = self.camera_controller.config.capture_device().to_string();
+        let profile_name = self.video_profiles[self.video_profile].clone();
+        let profile = &self.camera_controller.config.video.profile[&profile_name];
+        let stderr = std::fs::File::create(video_feed_log_path()).map_or_else(|_| Stdio::null(), Stdio::from);
+
+        let args: Vec<String> = [device]
+            .into_iter()
+            .chain(profile.args.iter().cloned())
+            .chain(["-hide_banner", "-loglevel", "error"].map(String::from))
+            .collect();
+
+        match self.camera_controller.executor().spawn_player(&args, stderr) {
+            Ok(child) => {
+                let retried = if attempt > 1 { format!(", attempt {}/{}", attempt, PREVIEW_LAUNCH_ATTEMPTS) } else { String::new() };
+                self.status_message = format!("Video feed started (PID: {}, profile: {}{}). Press '{}' again to stop.", child.id(), profile_name, retried, self.key_name(Action::ToggleVideo));
+                self.video_feed = Some(child);
+                self.video_launch = Some((attempt, Instant::now()));
+                self.camera_controller.set_preview_active(true);
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.set_error(format!("Failed to start video feed: ffplay not found; install ffmpeg ({})", e));
+            }
+            Err(e) => self.retry_video_feed(attempt, &e.to_string()),
+        }
+    }
+
+    /// Schedules the next launch attempt, or gives up once `PREVIEW_LAUNCH_ATTEMPTS` have failed
+    fn retry_video_feed(&mut self, failed_attempt: u32, reason: &str) {
+        if failed_attempt >= PREVIEW_LAUNCH_ATTEMPTS {
+            self.set_error(format!("Failed to start video feed after {} attempts: {}", failed_attempt, reason));
+            return;
+        }
+        let delay = PREVIEW_RETRY_BACKOFF * 2u32.pow(failed_attempt - 1);
+        self.video_retry = Some((Instant::now() + delay, failed_attempt + 1));
+        self.status_message = format!(
+            "Video feed attempt {}/{} failed ({}); retrying in {}ms.",
+            failed_attempt, PREVIEW_LAUNCH_ATTEMPTS, reason, delay.as_millis()
+        );
+    }
+
+    fn check_video_feed_retry(&mut self) {
+        if let Some((at, attempt)) = self.video_retry {
+            if Instant::now() >= at && self.stopping_player.is_none() {
+                self.video_retry = None;
+                self.launch_video_feed(attempt);
+            }
+        }
+    }
+
+    /// Notices a player that exited on its own (window closed, device error) and reaps it.
+    /// One that failed right after launch because the device was busy is relaunched.
+    fn check_video_feed_exited(&mut self) {
+        if let Some(child) = self.video_feed.as_mut() {
+            if let Ok(Some(status)) = child.try_wait() {
+                self.video_feed = None;
+                // The player can die of an unplug before `check_device` sees the node go
+                self.preview_was_running |= !Path::new(self.camera_controller.config.capture_device()).exists();
+                self.camera_controller.set_preview_active(false);
+                let launch = self.video_launch.take();
+                let log = std::fs::read_to_string(video_feed_log_path()).unwrap_or_default();
+                let last_line = log.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("").trim();
+                match launch {
+                    Some((attempt, launched))
+                        if !status.success()
+                            && launched.elapsed() < PREVIEW_LAUNCH_GRACE
+                            && last_line.to_lowercase().contains("busy") =>
+                    {
+                        self.retry_video_feed(attempt, last_line);
+                    }
+                    _ => {
+                        let message = match last_line {
+                            "" => format!("Video feed exited ({}).", status),
+                            _ => format!("Video feed exited ({}): {}", status, last_line),
+                        };
+                        if status.success() {
+                            self.status_message = message;
+                        } else {
+                            self.set_error(message);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Notices the capture device disappearing and coming back. A preview running at the time
+    /// is stopped, and with `video.auto_relaunch` started again once the device reappears.
+    fn check_device(&mut self) {
+        let device = self.camera_controller.config.capture_device().to_string();
+        let present = Path::new(&device).exists();
+        match (self.device_present, present) {
+            (Some(true), false) => {
+                self.preview_was_running |= self.video_feed.is_some() || self.video_retry.is_some();
+                self.video_retry = None;
+                self.stop_video_feed();
+                self.set_error(format!("Camera {} disconnected; waiting for it to reappear.", device));
+            }
+            (Some(false), true) => {
+                self.status_message = format!("Camera {} reconnected.", device);
+                if std::mem::take(&mut self.preview_was_running) && self.camera_controller.config.video.auto_relaunch {
+                    self.start_video_feed();
+                    self.status_message = format!("Camera {} reconnected; relaunching the preview. {}", device, self.status_message);
+                }
+            }
+            // Not seen yet: nothing to report until it has been there once
+            (None, false) => return,
+            _ => {}
+        }
+        self.device_present = Some(present);
+    }
+
+    /// Switch to the next video profile, restarting the preview if it is running
+    fn cycle_video_profile(&mut self) {
+        self.video_profile = (self.video_profile + 1) % self.video_profiles.len();
+        let profile_name = self.video_profiles[self.video_profile].clone();
+        if self.video_feed.is_some() {
+            self.stop_video_feed();
+            self.start_video_feed();
         } else {
-            // Start video feed in background
-            let device = self.camera_controller.config.device.clone();
-            let command = format!(
-                "ffplay {} -fflags nobuffer -flags low_delay -framedrop -sync ext -hide_banner -loglevel error >/dev/null 2>&1 & echo $!",
-                device
+            self.status_message = format!("Video profile: {}", profile_name);
+        }
+    }
+
+    fn adjust_command_interval(&mut self, faster: bool) {
+        if self.camera_controller.config.movement.adaptive_interval {
+            self.status_message = format!(
+                "Command interval is adaptive ({}ms); unset movement.adaptive_interval to set it by hand.",
+                self.command_interval.as_millis()
             );
-            
-            match Command::new("sh")
-                .arg("-c")
-                .arg(&command)
-                .output() {
+            return;
+        }
+        self.command_interval = if faster {
+            self.command_interval.saturating_sub(COMMAND_INTERVAL_STEP)
+        } else {
+            self.command_interval + COMMAND_INTERVAL_STEP
+        }
+        .clamp(COMMAND_INTERVAL_MIN, COMMAND_INTERVAL_MAX);
+        self.status_message = format!("Command interval: {}ms", self.command_interval.as_millis());
+    }
+
+    fn toggle_speed_info(&mut self) {
+        self.state.show_speed_info = !self.state.show_speed_info;
+        self.state_changed = true;
+        let shown = if self.state.show_speed_info { "shown" } else { "hidden" };
+        self.status_message = format!("Speed info {}.", shown);
+    }
+
+    /// Switches to the next built-in theme and remembers it
+    fn cycle_theme(&mut self) {
+        let current = self.state.theme.as_deref().and_then(|name| THEME_NAMES.iter().position(|n| *n == name));
+        let name = THEME_NAMES[current.map_or(1, |i| (i + 1) % THEME_NAMES.len())];
+        self.theme = Theme::named(name, &self.camera_controller.config.ui.theme).unwrap_or_default();
+        self.state.theme = Some(name.to_string());
+        self.state_changed = true;
+        self.status_message = format!("Theme: {}", name);
+    }
+
+    /// Captures the current position onto the end of the bookmark ring, saved on quit
+    fn push_bookmark(&mut self) {
+        self.bookmarks.push(Preset::at(self.get_pan(), self.get_tilt(), self.get_zoom()));
+        self.bookmark_index = Some(self.bookmarks.len() - 1);
+        self.bookmarks_changed = true;
+        self.status_message = format!(
+            "Saved bookmark {}/{} (written to {} on '{}')",
+            self.bookmarks.len(),
+            self.bookmarks.len(),
+            self.presets_store,
+            self.key_name(Action::Quit)
+        );
+    }
+
+    /// Opens the presets list on the last recalled bookmark, or closes it
+    fn toggle_presets_panel(&mut self) {
+        if self.presets_panel.take().is_some() {
+            self.status_message = "Presets list closed.".to_string();
+            return;
+        }
+        self.presets_panel = Some(self.bookmark_index.unwrap_or(0));
+        self.status_message = if self.bookmarks.is_empty() {
+            format!("No presets saved yet. Close the list and press '{}' to save one.", self.key_name(Action::Bookmark))
+        } else {
+            "Presets: ↑/↓ select, Enter overwrite with the current position, d delete, Esc close.".to_string()
+        };
+    }
+
+    /// Keys for the open presets list. Returns false for keys it doesn't use, which
+    /// then do what they're bound to.
+    fn handle_presets_key(&mut self, key: &KeyEvent) -> bool {
+        let Some(selected) = self.presets_panel else {
+            return false;
+        };
+        if !(key.modifiers - KeyModifiers::SHIFT).is_empty() {
+            return false;
+        }
+        let last = self.bookmarks.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Up => self.presets_panel = Some(selected.saturating_sub(1).min(last)),
+            KeyCode::Down => self.presets_panel = Some((selected + 1).min(last)),
+            KeyCode::Esc => {
+                self.presets_panel = None;
+                self.status_message = "Presets list closed.".to_string();
+            }
+            KeyCode::Char('d') => self.delete_preset(selected),
+            KeyCode::Enter => self.overwrite_preset(selected),
+            _ => return false,
+        }
+        true
+    }
+
+    /// The preset at `index` if it exists and is personal; otherwise explains why not
+    fn editable_preset(&mut self, index: usize) -> Option<&mut Preset> {
+        match self.bookmarks.get(index) {
+            None => {
+                self.status_message = "No preset selected; the list is empty.".to_string();
+                None
+            }
+            Some(preset) if preset.system => {
+                self.status_message = format!(
+                    "Preset {} is a system preset from {} and can't be changed here.",
+                    index + 1,
+                    self.paths.system_presets.display()
+                );
+                None
+            }
+            Some(_) => self.bookmarks.get_mut(index),
+        }
+    }
+
+    fn delete_preset(&mut self, index: usize) {
+        if self.editable_preset(index).is_none() {
+            return;
+        }
+        self.bookmarks.remove(index);
+        // Keep "next bookmark" moving on from the same place in the ring
+        self.bookmark_index = match self.bookmark_index {
+            Some(i) if i > index => Some(i - 1),
+            Some(i) if i == index => index.checked_sub(1),
+            other => other,
+        };
+        self.presets_panel = Some(index.min(self.bookmarks.len().saturating_sub(1)));
+        self.save_presets_now(format!("Deleted preset {}.", index + 1));
+    }
+
+    fn overwrite_preset(&mut self, index: usize) {
+        let (pan, tilt, zoom) = (self.get_pan(), self.get_tilt(), self.get_zoom());
+        let confirm = self.camera_controller.config.confirm_preset_overwrite;
+        let armed = self.pending_overwrite.take().is_some_and(|(slot, at)| slot == index && at.elapsed() < OVERWRITE_CONFIRM_TIMEOUT);
+        let Some(preset) = self.editable_preset(index) else {
+            return;
+        };
+        if confirm && !armed {
+            self.pending_overwrite = Some((index, Instant::now()));
+            self.status_message = format!("Slot {} occupied — press Enter again to overwrite.", index + 1);
+            return;
+        }
+        (preset.pan, preset.tilt, preset.zoom) = (pan, tilt, zoom);
+        self.save_presets_now(format!("Preset {} set to pan={} tilt={} zoom={}.", index + 1, pan, tilt, zoom));
+    }
+
+    /// Writes the personal presets to the presets store, unless they failed to load: the
+    /// ring then holds only this session's presets, which would replace the saved ones
+    fn save_presets(&self) -> anyhow::Result<()> {
+        if self.presets_load_failed {
+            anyhow::bail!("the saved presets failed to load at startup, so {} is left as it is", self.presets_store);
+        }
+        self.presets_store.save(&self.bookmarks)
+    }
+
+    /// Writes the personal presets straight away, for edits made in the presets list
+    fn save_presets_now(&mut self, done: String) {
+        match self.save_presets() {
+            Ok(()) => {
+                self.bookmarks_changed = false;
+                self.status_message = format!("{} Saved to {}.", done, self.presets_store);
+            }
+            Err(e) => {
+                self.bookmarks_changed = true;
+                self.set_error(format!("{} Not saved: {:#}", done, e));
+            }
+        }
+    }
+
+    /// The bookmark ring, system presets first
+    pub fn bookmarks(&self) -> &[Preset] {
+        &self.bookmarks
+    }
+
+    /// The selected row while the presets list is open
+    pub fn presets_panel(&self) -> Option<usize> {
+        self.presets_panel
+    }
+
+    /// Moves to the next captured bookmark, wrapping at the end of the ring
+    fn next_bookmark(&mut self) -> anyhow::Result<()> {
+        if self.bookmarks.is_empty() {
+            self.status_message = format!("No bookmarks yet. Press '{}' to capture one.", self.key_name(Action::Bookmark));
+            return Ok(());
+        }
+        let index = self.bookmark_index.map_or(0, |i| (i + 1) % self.bookmarks.len());
+        self.bookmark_index = Some(index);
+        let bookmark = &self.bookmarks[index];
+        self.go_to([bookmark.pan, bookmark.tilt, bookmark.zoom])?;
+        let bookmark = &self.bookmarks[index];
+        self.status_message = match bookmark.description() {
+            Some(description) => format!("Bookmark {}/{} ({}): {}", index + 1, self.bookmarks.len(), bookmark.source(), description),
+            None => format!("Bookmark {}/{} ({})", index + 1, self.bookmarks.len(), bookmark.source()),
+        };
+        if self.camera_controller.config.verify_presets {
+            match self.smooth_move {
+                Some(_) => self.verify_on_arrival = Some(index),
+                None => self.verify_preset(index),
+            }
+        }
+        Ok(())
+    }
+
+    /// Zooms straight to the `[zoom] stops` entry after the current one, or to the first
+    /// stop past the current zoom when it isn't at one, wrapping at the end
+    fn next_zoom_stop(&mut self) -> anyhow::Result<()> {
+        let stops = self.camera_controller.config.zoom.stops.clone();
+        if stops.is_empty() {
+            self.status_message = "No zoom stops configured; add stops = [...] under [zoom].".to_string();
+            return Ok(());
+        }
+        let zoom = self.get_zoom();
+        let index = match self.zoom_stop() {
+            Some(current) => (current + 1) % stops.len(),
+            None => stops.iter().position(|stop| *stop > zoom).unwrap_or(0),
+        };
+        self.camera_controller.set_axis(Axis::Zoom, stops[index])?;
+        self.zoom_stop_index = Some(index);
+        self.status_message = format!("Zoom stop {}/{} ({})", index + 1, stops.len(), stops[index]);
+        Ok(())
+    }
+
+    /// Describes the bookmark `n` would recall next, with percentages and degrees, without
+    /// sending anything
+    fn peek_bookmark(&mut self) {
+        if self.bookmarks.is_empty() {
+            self.status_message = format!("No bookmarks yet. Press '{}' to capture one.", self.key_name(Action::Bookmark));
+            return;
+        }
+        let index = self.bookmark_index.map_or(0, |i| (i + 1) % self.bookmarks.len());
+        let bookmark = &self.bookmarks[index];
+        let config = &self.camera_controller.config;
+        let readings: Vec<String> = Axis::ALL
+            .into_iter()
+            .filter(|axis| config.control(*axis).supported)
+            .map(|axis| {
+                let control = config.control(axis);
+                let value = [bookmark.pan, bookmark.tilt, bookmark.zoom][axis as usize];
+                match ui::degree_label(axis, value, control, &config.display) {
+                    Some(degrees) => format!("{} {} ({:.0}%, {})", axis.name(), value, control.percent(value), degrees),
+                    None => format!("{} {} ({:.0}%)", axis.name(), value, control.percent(value)),
+                }
+            })
+            .collect();
+        let description = bookmark.description().map_or_else(String::new, |description| format!(": {}", description));
+        self.status_message = format!(
+            "Next is bookmark {}/{} ({}){}: {}. Press '{}' to go there.",
+            index + 1,
+            self.bookmarks.len(),
+            bookmark.source(),
+            description,
+            readings.join(", "),
+            self.key_name(Action::NextBookmark)
+        );
+    }
+
+    /// Checks the camera reached bookmark `index`, appending the verdict to the status
+    fn verify_preset(&mut self, index: usize) {
+        let Some(bookmark) = self.bookmarks.get(index) else {
+            return;
+        };
+        let target = [bookmark.pan, bookmark.tilt, bookmark.zoom];
+        let tolerance = self.camera_controller.config.verify_tolerance;
+        let (verdict, failed) = match self.camera_controller.verify_position(target, tolerance) {
+            Ok(off) if off.is_empty() => (format!("preset {} reached", index + 1), false),
+            Ok(off) => {
+                let misses: Vec<String> = off.iter().map(|(axis, by)| format!("{} off by {}", axis.name(), by)).collect();
+                (format!("preset {} failed ({})", index + 1, misses.join(", ")), true)
+            }
+            Err(e) => (format!("preset {} couldn't be verified: {}", index + 1, e), true),
+        };
+        let message = format!("{}; {}.", self.status_message.trim_end_matches('.'), verdict);
+        if failed {
+            self.set_error(message);
+        } else {
+            self.status_message = message;
+        }
+    }
+
+    /// Moves all axes to `to`, gliding there when `movement.smooth_move_ms` is set
+    fn go_to(&mut self, to: [i32; 3]) -> anyhow::Result<()> {
+        match self.camera_controller.config.movement.smooth_move_ms {
+            Some(ms) => {
+                let from = Axis::ALL.map(|axis| self.camera_controller.position_of(axis));
+                self.smooth_move = Some(SmoothMove::new(from, to, Instant::now(), Duration::from_millis(ms)));
+                Ok(())
+            }
+            None => self.camera_controller.move_to(to[0], to[1], to[2]),
+        }
+    }
+
+    /// Swaps back to where the camera was before the last jump; pressed again, it returns
+    fn return_to_previous(&mut self) -> anyhow::Result<()> {
+        let Some(previous) = self.previous_position else {
+            self.status_message = "No previous position yet; jumps and bookmark recalls remember where they came from.".to_string();
+            return Ok(());
+        };
+        let here = Axis::ALL.map(|axis| self.camera_controller.position_of(axis));
+        self.verify_on_arrival = None;
+        self.go_to(previous)?;
+        self.previous_position = Some(here);
+        self.status_message = format!(
+            "Returned to pan={} tilt={} zoom={}. Press '{}' to go back.",
+            previous[0],
+            previous[1],
+            previous[2],
+            self.key_name(Action::ReturnToPrevious)
+        );
+        Ok(())
+    }
+
+    /// Sends the next point of a smooth move, ending it on arrival or on an error
+    fn advance_smooth_move(&mut self, now: Instant) {
+        let Some(glide) = self.smooth_move else {
+            return;
+        };
+        let (target, arrived) = glide.position_at(now);
+        let before = Axis::ALL.map(|axis| self.camera_controller.position_of(axis));
+        let result = self.camera_controller.move_to(target[0], target[1], target[2]);
+        for axis in Axis::ALL {
+            let moved = (self.camera_controller.position_of(axis) - before[axis as usize]).signum();
+            if moved != 0 {
+                self.active_motion[axis as usize] = Some((moved, now));
+            }
+        }
+        if let Err(e) = result {
+            self.set_error(format!("Error: {}", e));
+            self.smooth_move = None;
+            self.verify_on_arrival = None;
+        } else if arrived {
+            self.smooth_move = None;
+            if let Some(index) = self.verify_on_arrival.take() {
+                self.verify_preset(index);
+            }
+        }
+        self.last_command_time = now;
+    }
+
+    /// Re-reads `looks/` and applies the look after the last one applied, wrapping around
+    fn next_look(&mut self) -> anyhow::Result<()> {
+        let dir = Path::new(LOOKS_DIR);
+        let names = looks::list_looks(dir)?;
+        if names.is_empty() {
+            self.status_message = format!("No looks found. Add <name>.toml files to {}/.", LOOKS_DIR);
+            return Ok(());
+        }
+        let index = self
+            .last_look
+            .as_ref()
+            .and_then(|last| names.iter().position(|name| name == last))
+            .map_or(0, |i| (i + 1) % names.len());
+        let look = looks::load_look(dir, &names[index])?;
+        self.camera_controller.set_controls(&look.controls)?;
+        self.status_message = format!("Applied look '{}' ({}/{}): {}", look.name, index + 1, names.len(), look.summary());
+        self.last_look = Some(look.name);
+        Ok(())
+    }
+
+    /// Puts `text` on the system clipboard, falling back to a temp file on headless systems.
+    /// Returns a description of where the text went.
+    fn copy_to_clipboard(&mut self, text: &str) -> anyhow::Result<String> {
+        #[cfg(feature = "clipboard")]
+        {
+            // X11 clipboards only serve the text while the owner is alive, so keep it around
+            if self.clipboard.is_none() {
+                self.clipboard = arboard::Clipboard::new().ok();
+            }
+            if let Some(clipboard) = self.clipboard.as_mut() {
+                if clipboard.set_text(text).is_ok() {
+                    return Ok("clipboard".to_string());
+                }
+            }
+        }
+        write_fallback_clipboard(text)
+    }
+
+    fn copy_position(&mut self) {
+        let position = format!("pan={} tilt={} zoom={}", self.get_pan(), self.get_tilt(), self.get_zoom());
+        match self.copy_to_clipboard(&position) {
+            Ok(destination) => self.status_message = format!("Copied '{}' to {}", position, destination),
+            Err(e) => self.set_error(format!("Failed to copy position: {}", e)),
+        }
+    }
+
+    /// Stops the preview once no key has been pressed for `video.idle_timeout_secs`
+    fn check_preview_idle(&mut self) {
+        let Some(timeout) = self.camera_controller.config.video.idle_timeout_secs else {
+            return;
+        };
+        if self.video_feed.is_some() && self.last_input_time.elapsed() >= Duration::from_secs(timeout) {
+            self.stop_video_feed();
+            self.preview_idle_stopped = true;
+            self.status_message = format!("Video feed stopped after {}s without input.", timeout);
+        }
+    }
+
+    fn reset_to_defaults(&mut self) -> anyhow::Result<()> {
+        let count = self.camera_controller.reset_to_defaults()?;
+        self.status_message = match count {
+            0 => "No control defaults configured; set `default` under [pan], [tilt] or [zoom].".to_string(),
+            1 => "Reset 1 control to its default.".to_string(),
+            n => format!("Reset {} controls to their defaults.", n),
+        };
+        Ok(())
+    }
+
+    /// Moves an image control for its `[keys] image.<control>` key, at most once per command interval
+    fn step_image_control(&mut self, control: String, delta: i32) {
+        if self.locked {
+            self.refuse_while_locked();
+            return;
+        }
+        let now = Instant::now();
+        if now.duration_since(self.last_command_time) < self.command_interval {
+            return;
+        }
+        match self.camera_controller.set_image_control(&control, delta) {
+            Ok(value) => self.status_message = format!("{}: {}", control, value),
+            Err(e) => self.set_error(format!("Error: {}", e)),
+        }
+        self.last_command_time = now;
+    }
+
+    /// Starts a `[hooks]` command on its own thread so a slow command can't stall the UI
+    fn run_hook(&mut self, command: String) {
+        let executor = self.camera_controller.executor();
+        let results = self.hook_results.0.clone();
+        self.status_message = format!("Running hook: {}", command);
+        thread::spawn(move || {
+            let output = executor.shell(&command);
+            let _ = results.send((command, output));
+        });
+    }
+
+    /// Shows the outcome of finished hooks: the last line they printed, or why they failed
+    fn check_hooks(&mut self) {
+        while let Ok((command, result)) = self.hook_results.1.try_recv() {
+            match result {
                 Ok(output) => {
-                    if output.status.success() {
-                        // Parse the PID from the output
-                        if let Ok(pid_str) = String::from_utf8(output.stdout) {
-                            let pid = pid_str.trim();
-                            if let Ok(pid_num) = pid.parse::<u32>() {
-                                self.video_feed_pid = Some(pid_num);
-                                self.status_message = format!("Video feed started (PID: {}). Press 'v' again to stop.", pid_num);
-                            } else {
-                                self.status_message = "Video feed started in background. Press 'v' again to stop.".to_string();
-                            }
-                        } else {
-                            self.status_message = "Video feed started in background. Press 'v' again to stop.".to_string();
-                        }
-                    } else {
-                        self.status_message = "Failed to start video feed.".to_string();
+                    let text = if output.success { &output.stdout } else { &output.stderr };
+                    let last_line = text.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("").trim();
+                    match (output.success, last_line.is_empty()) {
+                        (true, true) => self.status_message = format!("Hook finished: {}", command),
+                        (true, false) => self.status_message = format!("Hook: {}", last_line),
+                        (false, true) => self.set_error(format!("Hook failed: {}", command)),
+                        (false, false) => self.set_error(format!("Hook failed: {}: {}", command, last_line)),
                     }
                 }
-                Err(e) => {
-                    self.status_message = format!("Failed to start video feed: {}", e);
+                Err(e) => self.set_error(format!("Hook failed to start: {}: {}", command, e)),
+            }
+        }
+    }
+
+    /// The axis and key-driven delta of a step move (arrows and zoom keys), `None` for other actions
+    fn step_delta(&self, action: Action) -> Option<(Axis, i32)> {
+        let config = &self.camera_controller.config;
+        let delta = |axis: Axis| config.control(axis).directed(config.control(axis).key_step());
+        match action {
+            Action::PanLeft => Some((Axis::Pan, -delta(Axis::Pan))),
+            Action::PanRight => Some((Axis::Pan, delta(Axis::Pan))),
+            Action::TiltUp => Some((Axis::Tilt, delta(Axis::Tilt))),
+            Action::TiltDown => Some((Axis::Tilt, -delta(Axis::Tilt))),
+            Action::ZoomIn => Some((Axis::Zoom, delta(Axis::Zoom))),
+            Action::ZoomOut => Some((Axis::Zoom, -delta(Axis::Zoom))),
+            _ => None,
+        }
+    }
+
+    /// Applies a step move to the display at once but holds the command back, sending it
+    /// early if the axis has been staged more than `debounce_steps` steps away
+    fn stage_move(&mut self, action: Action, axis: Axis, delta: i32, now: Instant) {
+        let before = self.camera_controller.position_of(axis);
+        self.camera_controller.stage(axis, self.camera_controller.stepped(axis, delta));
+        let moved = (self.camera_controller.position_of(axis) - before).signum();
+        if moved != 0 {
+            self.active_motion[axis as usize] = Some((moved, now));
+        }
+        self.last_staged = Some((action, now));
+        let movement = &self.camera_controller.config.movement;
+        let limit = movement.debounce_steps as i64 * self.camera_controller.config.control(axis).key_step() as i64;
+        if self.camera_controller.staged_distance(axis) > limit {
+            self.flush_staged_moves(now);
+        }
+    }
+
+    /// Sends everything `stage_move` has held back
+    fn flush_staged_moves(&mut self, now: Instant) {
+        if let Some((action, _)) = self.last_staged.take() {
+            let result = self.camera_controller.flush();
+            self.finish_command(action, result, now);
+        }
+    }
+
+    /// Sends the queued step moves, one per axis, in a single batched command
+    fn flush_pending_moves(&mut self, now: Instant) {
+        let moves: Vec<Action> = self.pending_moves.iter_mut().filter_map(Option::take).collect();
+        let Some(&last) = moves.last() else {
+            return;
+        };
+        let before = Axis::ALL.map(|axis| self.camera_controller.position_of(axis));
+        let mut target = before;
+        for (axis, delta) in moves.iter().filter_map(|action| self.step_delta(*action)) {
+            let value = self.camera_controller.stepped(axis, delta);
+            if !self.camera_controller.within_deadband(axis, value) {
+                target[axis as usize] = value;
+            }
+        }
+        let result = self.camera_controller.move_to(target[0], target[1], target[2]);
+        for axis in Axis::ALL {
+            let moved = (self.camera_controller.position_of(axis) - before[axis as usize]).signum();
+            if moved != 0 {
+                self.active_motion[axis as usize] = Some((moved, now));
+            }
+        }
+        self.finish_command(last, result, now);
+    }
+
+    /// Runs a single-axis move and keeps its outcome for `finish_command`
+    fn track_move(&mut self, axis: Axis, send: impl FnOnce(&mut CameraController) -> anyhow::Result<MoveOutcome>) -> anyhow::Result<()> {
+        let outcome = send(&mut self.camera_controller)?;
+        self.move_outcome = Some((axis, outcome));
+        Ok(())
+    }
+
+    /// Runs the action a key is bound to
+    fn perform(&mut self, action: Action) -> anyhow::Result<()> {
+        if let Some((axis, delta)) = self.step_delta(action) {
+            return self.track_move(axis, |camera| match axis {
+                Axis::Pan => camera.set_pan(delta),
+                Axis::Tilt => camera.set_tilt(delta),
+                Axis::Zoom => camera.set_zoom(delta),
+            });
+        }
+        let before = Axis::ALL.map(|axis| self.camera_controller.position_of(axis));
+        let config = &self.camera_controller.config;
+        let (pan, tilt, zoom) = (config.pan.directed(1), config.tilt.directed(1), config.zoom.directed(1));
+        let result = match action {
+            Action::NudgeLeft => self.track_move(Axis::Pan, |camera| camera.nudge_device_step(Axis::Pan, -pan)),
+            Action::NudgeRight => self.track_move(Axis::Pan, |camera| camera.nudge_device_step(Axis::Pan, pan)),
+            Action::NudgeUp => self.track_move(Axis::Tilt, |camera| camera.nudge_device_step(Axis::Tilt, tilt)),
+            Action::NudgeDown => self.track_move(Axis::Tilt, |camera| camera.nudge_device_step(Axis::Tilt, -tilt)),
+            Action::JumpLeft => self.track_move(Axis::Pan, |camera| camera.jump_to_limit(Axis::Pan, -pan)),
+            Action::JumpRight => self.track_move(Axis::Pan, |camera| camera.jump_to_limit(Axis::Pan, pan)),
+            Action::JumpUp => self.track_move(Axis::Tilt, |camera| camera.jump_to_limit(Axis::Tilt, tilt)),
+            Action::JumpDown => self.track_move(Axis::Tilt, |camera| camera.jump_to_limit(Axis::Tilt, -tilt)),
+            Action::JumpZoomIn => self.track_move(Axis::Zoom, |camera| camera.jump_to_limit(Axis::Zoom, zoom)),
+            Action::JumpZoomOut => self.track_move(Axis::Zoom, |camera| camera.jump_to_limit(Axis::Zoom, -zoom)),
+            Action::NextZoomStop => self.next_zoom_stop(),
+            // Step moves were handled above
+            Action::PanLeft | Action::PanRight | Action::TiltUp | Action::TiltDown | Action::ZoomIn | Action::ZoomOut => Ok(()),
+            Action::ToggleVideo => {
+                self.toggle_video_feed();
+                Ok(())
+            }
+            Action::CycleVideoProfile => {
+                self.cycle_video_profile();
+                Ok(())
+            }
+            Action::Bookmark => {
+                self.push_bookmark();
+                Ok(())
+            }
+            Action::NextBookmark => self.next_bookmark(),
+            Action::PeekBookmark => {
+                self.peek_bookmark();
+                Ok(())
+            }
+            Action::ReturnToPrevious => self.return_to_previous(),
+            Action::PresetsPanel => {
+                self.toggle_presets_panel();
+                Ok(())
+            }
+            Action::CopyPosition => {
+                self.copy_position();
+                Ok(())
+            }
+            Action::NextLook => self.next_look(),
+            Action::ResetDefaults => self.reset_to_defaults(),
+            Action::ToggleSpeedInfo => {
+                self.toggle_speed_info();
+                Ok(())
+            }
+            Action::CycleTheme => {
+                self.cycle_theme();
+                Ok(())
+            }
+            Action::FasterCommands => {
+                self.adjust_command_interval(true);
+                Ok(())
+            }
+            Action::SlowerCommands => {
+                self.adjust_command_interval(false);
+                Ok(())
+            }
+            Action::CycleMotionProfile => {
+                self.cycle_motion_profile();
+                Ok(())
+            }
+            Action::ToggleLock => {
+                self.locked = !self.locked;
+                if self.locked {
+                    self.drop_unsent_moves();
                 }
+                self.status_message = match self.locked {
+                    true => format!("Camera locked; moves are ignored until '{}' is pressed again.", self.key_name(Action::ToggleLock)),
+                    false => "Camera unlocked.".to_string(),
+                };
+                Ok(())
+            }
+            Action::ToggleArrowZoom => {
+                self.arrows_zoom = !self.arrows_zoom;
+                self.status_message = match self.arrows_zoom {
+                    true => "Up/down now zoom; left/right still pan.".to_string(),
+                    false => "Up/down now tilt.".to_string(),
+                };
+                Ok(())
+            }
+            // Dispatched as the move it repeats, so never performed itself
+            Action::RepeatLast => Ok(()),
+            Action::Quit => {
+                self.should_quit = true;
+                Ok(())
+            }
+            Action::QuitWithoutSaving => {
+                self.save_on_quit = false;
+                self.should_quit = true;
+                Ok(())
+            }
+        };
+        let moved = self.smooth_move.is_some() || Axis::ALL.map(|axis| self.camera_controller.position_of(axis)) != before;
+        if action.is_jump() && moved {
+            self.previous_position = Some(before);
+        }
+        result
+    }
+
+    /// Returns home once no user command has succeeded for `watchdog_timeout_secs`,
+    /// then re-arms so an unattended camera keeps getting re-centred
+    fn check_watchdog(&mut self) {
+        let Some((remaining, timeout)) = self.watchdog_countdown() else {
+            return;
+        };
+        if remaining.is_zero() {
+            self.last_user_command_time = Instant::now();
+            match self.camera_controller.reset_to_home() {
+                Ok(()) => self.status_message = format!("Watchdog: returned home after {}s without a command.", timeout.as_secs()),
+                Err(e) => self.set_error(format!("Watchdog: failed to return home: {}", e)),
             }
         }
     }
 
     pub fn update(&mut self, event: InputEvent) {
+        self.handle_event(event);
+        self.record_status();
+    }
+
+    fn handle_event(&mut self, event: InputEvent) {
         match event {
+            InputEvent::Key(key) if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Raw mode delivers Ctrl+C as a key rather than SIGINT; always treat it as quit
+                self.should_quit = true;
+            }
             InputEvent::Key(key) => {
-                let now = Instant::now();
-                if now.duration_since(self.last_command_time) >= self.command_interval {
-                    let result = match (key.code, key.modifiers) {
-                        (KeyCode::Left, _) => self.camera_controller.set_pan(-self.camera_controller.config.pan.step),
-                        (KeyCode::Right, _) => self.camera_controller.set_pan(self.camera_controller.config.pan.step),
-                        (KeyCode::Up, KeyModifiers::SHIFT) => self.camera_controller.set_zoom(self.camera_controller.config.zoom.step),
-                        (KeyCode::Down, KeyModifiers::SHIFT) => self.camera_controller.set_zoom(-self.camera_controller.config.zoom.step),
-                        (KeyCode::Up, _) => self.camera_controller.set_tilt(self.camera_controller.config.tilt.step),
-                        (KeyCode::Down, _) => self.camera_controller.set_tilt(-self.camera_controller.config.tilt.step),
-                        (KeyCode::Char('v'), _) => {
-                            self.toggle_video_feed();
-                            Ok(())
-                        }
-                        (KeyCode::Char('q'), _) => {
-                            self.should_quit = true;
-                            Ok(())
-                        }
-                        _ => Ok(()), // Ignore other keys
-                    };
-
-                    match result {
-                        Ok(_) => {
-                            if !matches!(key.code, KeyCode::Char('v')) {
-                                self.status_message = "Command sent.".to_string();
-                            }
-                        }
-                        Err(e) => self.status_message = format!("Error: {}", e),
+                self.last_input_time = Instant::now();
+                if self.handle_presets_key(&key) {
+                    return;
+                }
+                let action = self.keybindings().action_for(&key).map(|action| self.arrow_mode_action(action));
+                if std::mem::take(&mut self.preview_idle_stopped)
+                    && self.camera_controller.config.video.auto_resume
+                    && action != Some(Action::ToggleVideo)
+                {
+                    self.start_video_feed();
+                }
+                let Some(action) = action else {
+                    if let Some((control, delta)) = self.keybindings().image_step_for(&key) {
+                        self.step_image_control(control.to_string(), delta);
+                    } else if let Some(command) = self.camera_controller.config.hooks.command_for(&key) {
+                        self.run_hook(command.to_string());
                     }
-                    self.last_command_time = now;
+                    return; // Ignore other keys
+                };
+                self.dispatch(action);
+            }
+            // The presets list sits over the panels and takes the arrows, so the mouse waits too
+            InputEvent::Mouse(_) if self.presets_panel.is_some() => {}
+            InputEvent::Mouse(mouse) => {
+                if let Some(action) = self.action_for_mouse(&mouse) {
+                    self.last_input_time = Instant::now();
+                    self.dispatch(action);
+                } else if let Some((pan, tilt)) = self.click_target(&mouse) {
+                    self.last_input_time = Instant::now();
+                    self.centre_on(pan, tilt);
                 }
             }
+            InputEvent::Shutdown => {
+                self.should_quit = true;
+            }
             InputEvent::Tick => {
                 // Update any time-sensitive UI elements if needed
+                let now = Instant::now();
+                if now.duration_since(self.last_command_time) >= self.command_interval {
+                    self.flush_pending_moves(now);
+                    self.advance_smooth_move(now);
+                }
+                let quiet = Duration::from_millis(self.camera_controller.config.movement.debounce_ms.unwrap_or(0));
+                if self.last_staged.is_some_and(|(_, at)| now.duration_since(at) >= quiet) {
+                    self.flush_staged_moves(now);
+                }
+                self.check_device();
+                self.check_video_feed_exited();
+                self.reap_stopping_player(now);
+                self.check_video_feed_retry();
+                self.check_preview_idle();
+                self.check_watchdog();
+                self.check_hooks();
             }
         }
     }
 
+    /// Runs an action from a key or the mouse, subject to the command interval
+    fn dispatch(&mut self, action: Action) {
+        if action == Action::RepeatLast {
+            // Nothing to repeat until something has moved
+            if let Some(last) = self.last_move {
+                self.dispatch(last);
+            }
+            return;
+        }
+        if action.axis().is_some_and(|axis| !self.camera_controller.config.control(axis).supported) {
+            return; // Already reported once at startup
+        }
+        if self.locked && action.is_camera_command() {
+            self.refuse_while_locked();
+            return;
+        }
+        if action.axis().is_some() && !action.is_jump() {
+            self.last_move = Some(action);
+        }
+        if action.is_camera_command() {
+            self.smooth_move = None; // Any other move takes over from a glide
+            self.verify_on_arrival = None;
+        }
+        let now = Instant::now();
+        if let Some((axis, delta)) = self.step_delta(action).filter(|_| self.camera_controller.config.movement.debounce_ms.is_some()) {
+            self.stage_move(action, axis, delta, now);
+            return;
+        }
+        let due = now.duration_since(self.last_command_time) >= self.command_interval;
+        if let Some((axis, _)) = self.step_delta(action).filter(|_| self.camera_controller.config.movement.queue_moves) {
+            // Keys within the interval, and keys joining moves already waiting, go out together
+            if !due || self.pending_moves.iter().any(Option::is_some) {
+                self.pending_moves[axis as usize] = Some(action);
+                if due {
+                    self.flush_pending_moves(now);
+                }
+                return;
+            }
+        }
+        if due {
+            let before = action.axis().map(|axis| (axis, self.camera_controller.position_of(axis)));
+            let result = self.perform(action);
+            if let Some((axis, before)) = before {
+                let moved = (self.camera_controller.position_of(axis) - before).signum();
+                if moved != 0 {
+                    self.active_motion[axis as usize] = Some((moved, now));
+                }
+            }
+            self.finish_command(action, result, now);
+        }
+    }
+
+    /// The scroll wheel moves whichever axis's panel is under the cursor: up (or right)
+    /// pans right, tilts up and zooms in
+    fn action_for_mouse(&self, mouse: &MouseEvent) -> Option<Action> {
+        let cell = CellPosition::new(mouse.column, mouse.row);
+        let axis = Axis::ALL.into_iter().find(|axis| self.panel_areas.get()[*axis as usize].contains(cell))?;
+        let forward = match mouse.kind {
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollRight => true,
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollLeft => false,
+            _ => return None,
+        };
+        Some(match (axis, forward) {
+            (Axis::Pan, true) => Action::PanRight,
+            (Axis::Pan, false) => Action::PanLeft,
+            (Axis::Tilt, true) => Action::TiltUp,
+            (Axis::Tilt, false) => Action::TiltDown,
+            (Axis::Zoom, true) => Action::ZoomIn,
+            (Axis::Zoom, false) => Action::ZoomOut,
+        })
+    }
+
+    /// Where a left click in the Pan and Tilt panels aims. Together they form one box:
+    /// left to right is pan `min` to `max`, bottom to top is tilt `min` to `max`.
+    /// An axis without a panel keeps its current value.
+    fn click_target(&self, mouse: &MouseEvent) -> Option<(i32, i32)> {
+        if !matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left)) {
+            return None;
+        }
+        let [pan_area, tilt_area, _] = self.panel_areas.get();
+        let area = match (pan_area.is_empty(), tilt_area.is_empty()) {
+            (true, true) => return None,
+            (false, true) => pan_area,
+            (true, false) => tilt_area,
+            (false, false) => pan_area.union(tilt_area),
+        };
+        if !area.contains(CellPosition::new(mouse.column, mouse.row)) {
+            return None;
+        }
+        // 0.0 at the first cell, 1.0 at the last
+        let fraction = |offset: u16, length: u16| if length > 1 { offset as f64 / (length - 1) as f64 } else { 0.5 };
+        let config = &self.camera_controller.config;
+        let value = |axis: Axis, fraction: f64| {
+            let control = config.control(axis);
+            if !control.supported {
+                return self.camera_controller.position_of(axis);
+            }
+            (control.min as f64 + fraction * (control.max as f64 - control.min as f64)).round() as i32
+        };
+        Some((
+            value(Axis::Pan, fraction(mouse.column - area.x, area.width)),
+            value(Axis::Tilt, 1.0 - fraction(mouse.row - area.y, area.height)),
+        ))
+    }
+
+    /// In arrow-zoom mode the tilt keys zoom, following `shift_up_zooms_in` like Shift would
+    fn arrow_mode_action(&self, action: Action) -> Action {
+        let up_zooms_in = self.camera_controller.config.movement.shift_up_zooms_in;
+        match (self.arrows_zoom, action) {
+            (true, Action::TiltUp) if up_zooms_in => Action::ZoomIn,
+            (true, Action::TiltUp) => Action::ZoomOut,
+            (true, Action::TiltDown) if up_zooms_in => Action::ZoomOut,
+            (true, Action::TiltDown) => Action::ZoomIn,
+            _ => action,
+        }
+    }
+
+    /// Drops every move that hasn't reached the camera yet: a glide in progress and queued
+    /// or debounced step moves
+    fn drop_unsent_moves(&mut self) {
+        self.smooth_move = None;
+        self.verify_on_arrival = None;
+        self.pending_moves = [None; 3];
+        self.last_staged = None;
+        self.camera_controller.discard_staged();
+    }
+
+    fn refuse_while_locked(&mut self) {
+        self.status_message = format!("Locked; press '{}' to unlock before moving the camera.", self.key_name(Action::ToggleLock));
+    }
+
+    /// Moves pan and tilt to a clicked point in one batched command, zoom staying put
+    fn centre_on(&mut self, pan: i32, tilt: i32) {
+        if self.locked {
+            self.refuse_while_locked();
+            return;
+        }
+        let now = Instant::now();
+        self.pending_moves = [None; 3];
+        self.last_staged = None;
+        self.smooth_move = None;
+        let before = Axis::ALL.map(|axis| self.camera_controller.position_of(axis));
+        let result = self.camera_controller.move_to(pan, tilt, before[Axis::Zoom as usize]);
+        for axis in Axis::ALL {
+            let moved = (self.camera_controller.position_of(axis) - before[axis as usize]).signum();
+            if moved != 0 {
+                self.active_motion[axis as usize] = Some((moved, now));
+                self.previous_position = Some(before);
+            }
+        }
+        let status = format!(
+            "Aimed at pan {}, tilt {}.",
+            self.camera_controller.position_of(Axis::Pan),
+            self.camera_controller.position_of(Axis::Tilt)
+        );
+        self.report_command(true, None, Some(status), result, now);
+    }
+
+    /// Records where the Pan, Tilt and Zoom panels were drawn
+    pub fn set_panel_areas(&self, areas: [Rect; 3]) {
+        self.panel_areas.set(areas);
+    }
+
+    /// Spells out what a move did for `--plain`, e.g. "Pan increased to 120, 63 percent"
+    fn describe_position_change(&mut self, focus: Option<Axis>) -> String {
+        let config = &self.camera_controller.config;
+        let mut changes = Vec::new();
+        for axis in Axis::ALL {
+            let (value, before) = (self.camera_controller.position_of(axis), self.announced[axis as usize]);
+            if value == before && focus != Some(axis) {
+                continue;
+            }
+            let (first, rest) = axis.name().split_at(1);
+            let verb = match value.cmp(&before) {
+                std::cmp::Ordering::Greater => "increased to",
+                std::cmp::Ordering::Less => "decreased to",
+                std::cmp::Ordering::Equal => "unchanged at",
+            };
+            changes.push(format!("{}{} {} {}, {:.0} percent", first.to_uppercase(), rest, verb, value, config.control(axis).percent(value)));
+            self.announced[axis as usize] = value;
+        }
+        changes.join("; ")
+    }
+
+    /// Feedback after a key's command ran: the bell, the watchdog and the status message
+    fn finish_command(&mut self, action: Action, result: anyhow::Result<()>, now: Instant) {
+        let status = (!action.sets_own_status()).then(|| "Command sent.".to_string());
+        self.report_command(action.is_camera_command(), action.axis(), status, result, now);
+    }
+
+    /// `finish_command` for any command, including clicks, which have no action. `status`
+    /// is reported on success unless there's something more specific to say; `None` keeps
+    /// the status the command set itself. `--plain` always announces `focus`.
+    fn report_command(
+        &mut self,
+        camera_command: bool,
+        focus: Option<Axis>,
+        status: Option<String>,
+        result: anyhow::Result<()>,
+        now: Instant,
+    ) {
+        if camera_command && self.camera_controller.config.audible_feedback {
+            ring_bell(if result.is_ok() { 1 } else { 2 });
+        }
+
+        if camera_command && result.is_ok() {
+            self.last_user_command_time = now;
+        }
+
+        let outcome = self
+            .move_outcome
+            .take()
+            .and_then(|(axis, outcome)| describe_outcome(axis, outcome, self.camera_controller.config.control(axis)));
+        match result {
+            Ok(_) => {
+                if let Some(note) = self.camera_controller.take_write_note() {
+                    self.status_message = format!("Command sent. Note: {}", note);
+                } else if status.is_some() && self.plain {
+                    self.status_message = self.describe_position_change(focus);
+                } else if let Some(outcome) = outcome {
+                    self.status_message = outcome;
+                } else if let Some(status) = status {
+                    self.status_message = status;
+                }
+            }
+            Err(e) if self.video_feed.is_some() && is_busy_error(&e) => {
+                self.set_error(format!(
+                    "Error: camera is busy while the video preview is running; the player may hold the device exclusively. \
+                     Stop the preview with '{}' or set `preview_control_device`. ({})",
+                    self.key_name(Action::ToggleVideo),
+                    e
+                ));
+            }
+            Err(e) => self.set_error(format!("Error: {}", e)),
+        }
+        self.last_command_time = now;
+        self.adapt_command_interval();
+    }
+
+    /// With `movement.adaptive_interval`: follows the latency of the calls made so far
+    fn adapt_command_interval(&mut self) {
+        let config = &self.camera_controller.config;
+        if let Some(interval) = config.movement.adaptive_interval(&self.camera_controller.latency()) {
+            self.command_interval = interval;
+        }
+    }
+
+    pub fn keybindings(&self) -> &KeyBindings {
+        &self.camera_controller.config.keys
+    }
+
+    /// The key bound to `action`, for status messages
+    fn key_name(&self, action: Action) -> String {
+        self.keybindings().key_for(action).map_or_else(|| "(unbound)".to_string(), |key| key.to_string())
+    }
+
+    /// Minimum time between commands, which bounds how often a held key moves the camera
+    pub fn command_interval(&self) -> Duration { self.command_interval }
+
+    /// Whether the video preview player is running
+    pub fn preview_running(&self) -> bool { self.video_feed.is_some() }
+
+    /// The active `[profile.<name>]`, if any
+    pub fn motion_profile(&self) -> Option<&str> { self.motion_profile.as_deref() }
+
+    pub fn show_speed_info(&self) -> bool { self.state.show_speed_info }
+
+    /// Time left until the watchdog returns home, and its full timeout; `None` while it's
+    /// off or the camera is locked
+    pub fn watchdog_countdown(&self) -> Option<(Duration, Duration)> {
+        let timeout = Duration::from_secs(self.camera_controller.config.watchdog_timeout_secs.filter(|_| !self.locked)?);
+        Some((timeout.saturating_sub(self.last_user_command_time.elapsed()), timeout))
+    }
+
+    /// Whether the lock key has the camera locked against moves
+    pub fn locked(&self) -> bool { self.locked }
+
+    /// The `[zoom] stops` entry the camera is at: the one last chosen with the quick-zoom
+    /// key, until zoom moves off it
+    pub fn zoom_stop(&self) -> Option<usize> {
+        self.zoom_stop_index.filter(|index| self.camera_controller.config.zoom.stops.get(*index) == Some(&self.get_zoom()))
+    }
+
+    /// Whether the tilt keys are switched to zoom
+    pub fn arrows_zoom(&self) -> bool { self.arrows_zoom }
+
+    pub fn theme(&self) -> &Theme { &self.theme }
+
+    /// +1 or -1 while `axis` is moving (its control value went up or down within the last
+    /// command interval plus `MOTION_INDICATOR_HOLD`), otherwise 0
+    pub fn motion_direction(&self, axis: Axis) -> i32 {
+        match self.active_motion[axis as usize] {
+            Some((direction, at)) if at.elapsed() <= self.command_interval + MOTION_INDICATOR_HOLD => direction,
+            _ => 0,
+        }
+    }
+
     pub fn get_pan(&self) -> i32 { self.camera_controller.get_pan() }
     pub fn get_tilt(&self) -> i32 { self.camera_controller.get_tilt() }
     pub fn get_zoom(&self) -> i32 { self.camera_controller.get_zoom() }
@@ -126,12 +1684,683 @@ impl App {
         self.camera_controller.get_zoom_adjusted_tilt_step() 
     }
 
-    /// Cleanup method to be called when the app is shutting down
-    pub fn cleanup(&mut self) {
-        if let Some(pid) = self.video_feed_pid.take() {
-            let _ = Command::new("kill")
-                .arg(pid.to_string())
-                .output();
+    /// Cleanup method to be called when the app is shutting down. Stops the preview and,
+    /// unless the session was quit without saving, writes the bookmarks and preferences
+    /// that changed.
+    pub fn cleanup(&mut self) -> anyhow::Result<()> {
+        self.stop_video_feed();
+        // Exiting anyway, so wait for the player here rather than on ticks
+        while self.stopping_player.is_some() {
+            self.reap_stopping_player(Instant::now());
+            thread::sleep(Duration::from_millis(10));
+        }
+        if self.last_staged.take().is_some() {
+            let _ = self.camera_controller.flush(); // Leave the camera where the display says it is
+        }
+        if !self.save_on_quit {
+            return Ok(());
+        }
+        // Preferences are still saved when the presets can't be
+        let presets = if self.bookmarks_changed { self.save_presets() } else { Ok(()) };
+        if self.state_changed {
+            state::save_state(&self.paths.state, &self.state)?;
+        }
+        presets
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake::FakeCamera;
+
+    fn press(app: &mut App, code: KeyCode, modifiers: KeyModifiers, times: usize) {
+        for _ in 0..times {
+            app.update(InputEvent::Key(KeyEvent::new(code, modifiers)));
+        }
+    }
+
+    #[test]
+    fn key_sequence_moves_the_fake_camera() {
+        let camera = FakeCamera::new().with_control("pan_absolute", -100, 25, 0);
+        let mut config = CameraConfig::default();
+        config.pan = crate::camera::ControlConfig { min: -100, max: 100, step: 10, ..config.pan };
+        config.tilt = crate::camera::ControlConfig { min: -100, max: 100, step: 10, ..config.tilt };
+        config.verify_writes = true;
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+
+        // The camera stops pan at 25 even though the config allows 100; verify_writes adopts that
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 3);
+        assert_eq!((camera.value("pan_absolute"), app.get_pan()), (Some(25), 25));
+
+        // Zooming in to 20 shrinks the pan/tilt step from 10 to 8
+        press(&mut app, KeyCode::Up, KeyModifiers::SHIFT, 2);
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Down, KeyModifiers::NONE, 1);
+        assert_eq!(
+            ["pan_absolute", "tilt_absolute", "zoom_absolute"].map(|name| camera.value(name)),
+            [Some(17), Some(-8), Some(20)]
+        );
+        assert!(!app.should_quit);
+        press(&mut app, KeyCode::Char('q'), KeyModifiers::NONE, 1);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn status_changes_are_logged_with_the_device() {
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(FakeCamera::new()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 2);
+        press(&mut app, KeyCode::Char(']'), KeyModifiers::NONE, 1);
+        let messages: Vec<&str> = app.status_history().map(|(_, message)| message.as_str()).collect();
+        assert_eq!(messages, ["Press 'q' to quit. See Help for all keys.", "Command sent.", "Command interval: 25ms"]);
+
+        let path = std::env::temp_dir().join(format!("tiny-ptz-session-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        app.write_session_log(&path, Path::new("config.toml")).unwrap();
+        app.write_session_log(&path, Path::new("config.toml")).unwrap();
+        let log = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(log.matches("== tiny-ptz session ended ").count(), 2, "{}", log);
+        assert!(log.contains("\ndevice: /dev/video0\n"), "{}", log);
+        assert!(log.contains(" UTC  Command interval: 25ms\n"), "{}", log);
+    }
+
+    #[test]
+    fn timestamps_are_utc_calendar_dates() {
+        assert_eq!(format_timestamp(UNIX_EPOCH + Duration::from_secs(1_700_000_000)), "2023-11-14 22:13:20 UTC");
+        assert_eq!(format_timestamp(UNIX_EPOCH + Duration::from_secs(951_782_400)), "2000-02-29 00:00:00 UTC");
+    }
+
+    #[test]
+    fn motion_profiles_overlay_and_restore_the_base_settings() {
+        let config: CameraConfig = toml::from_str("\
+motion_profile = \"precise\"
+
+[pan]
+step = 20000
+
+[profile.fast]
+pan_step = 60000
+command_interval_ms = 50
+
+[profile.precise]
+pan_step = 3600
+zoom_max_step = 2
+").unwrap();
+        config.validate().unwrap();
+        let mut app = App::with_paths(config, Arc::new(FakeCamera::new()), AppPaths::scratch());
+        let settings = |app: &App| {
+            let config = &app.camera_controller.config;
+            (app.motion_profile().map(str::to_string), config.pan.step, config.zoom.max_step, app.command_interval())
+        };
+        let ms = Duration::from_millis;
+        assert_eq!(settings(&app), (Some("precise".to_string()), 3600, Some(2), ms(100)));
+
+        app.perform(Action::CycleMotionProfile).unwrap();
+        assert_eq!(settings(&app), (None, 20000, None, ms(100)));
+        assert_eq!(app.status_message, "Motion profile: base settings");
+        app.perform(Action::CycleMotionProfile).unwrap();
+        assert_eq!(settings(&app), (Some("fast".to_string()), 60000, None, ms(50)));
+
+        let unknown: CameraConfig = toml::from_str("motion_profile = \"slow\"").unwrap();
+        assert!(unknown.validate().is_err());
+        let invalid: CameraConfig = toml::from_str("[profile.broken]\ntilt_step = 0").unwrap();
+        assert!(format!("{:#}", invalid.validate().unwrap_err()).contains("[profile.broken]"));
+    }
+
+    #[test]
+    fn alt_arrows_jump_to_the_limits_in_one_command() {
+        let camera = FakeCamera::new();
+        let config: CameraConfig = toml::from_str("[pan]\nmin = -1000\nmax = 1000\ninvert = true").unwrap();
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        let before = camera.commands().len();
+
+        // Inverted pan: Alt+Left heads for max, as Left would
+        press(&mut app, KeyCode::Left, KeyModifiers::ALT, 1);
+        press(&mut app, KeyCode::Up, KeyModifiers::ALT | KeyModifiers::SHIFT, 1);
+        press(&mut app, KeyCode::Down, KeyModifiers::ALT, 1);
+        assert_eq!(
+            ["pan_absolute", "tilt_absolute", "zoom_absolute"].map(|name| camera.value(name)),
+            [Some(1000), Some(-324000), Some(100)]
+        );
+        assert_eq!(camera.commands().len(), before + 3);
+
+        // Already there: nothing more goes out, and the status says why
+        press(&mut app, KeyCode::Down, KeyModifiers::ALT, 1);
+        assert_eq!(camera.commands().len(), before + 3);
+        assert_eq!(app.status_message, "Tilt is already at -324000; nothing sent.");
+    }
+
+    #[test]
+    fn a_move_held_back_by_the_deadband_near_a_limit_says_so() {
+        let camera = FakeCamera::new().with_control("pan_absolute", 0, 100, 97);
+        let mut config = CameraConfig::default();
+        config.pan = ControlConfig { min: 0, max: 100, step: 10, deadband: 5, ..config.pan };
+        config.movement.zoom_adjust = false;
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        app.camera_controller.sync_from_device().unwrap();
+        app.command_interval = Duration::ZERO;
+
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        assert_eq!(app.status_message, "Pan move of 3 is within its deadband; nothing sent.");
+        press(&mut app, KeyCode::Right, KeyModifiers::ALT, 1);
+        assert_eq!(app.status_message, "Command sent.");
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        assert_eq!(app.status_message, "Pan is at its limit (100); nothing sent.");
+    }
+
+    #[test]
+    fn keys_within_the_interval_are_sent_together() {
+        let camera = FakeCamera::new();
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        let sets = || camera.commands().into_iter().filter(|args| args.contains(&"--set-ctrl".to_string())).collect::<Vec<_>>();
+
+        // The first key goes straight out; the next ones wait, the later pan replacing the earlier
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        app.command_interval = Duration::from_secs(3600);
+        press(&mut app, KeyCode::Up, KeyModifiers::SHIFT, 1);
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE, 1);
+        assert_eq!(sets().len(), 1);
+
+        app.command_interval = Duration::ZERO;
+        app.update(InputEvent::Tick);
+        let sets = sets();
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[1].last().unwrap(), "pan_absolute=0,zoom_absolute=10");
+        assert_eq!((app.motion_direction(Axis::Pan), app.motion_direction(Axis::Zoom)), (-1, 1));
+    }
+
+    #[test]
+    fn presets_that_failed_to_load_are_never_overwritten() {
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(FakeCamera::new()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        let path = std::env::temp_dir().join(format!("tiny-ptz-presets-broken-{}.toml", std::process::id()));
+        std::fs::write(&path, "[[bookmark]]\npan = \"oops\"\n").unwrap();
+        app.presets_store = PresetsStore::File(path.clone());
+        app.presets_load_failed = true;
+
+        // Edits in the presets list aren't written either
+        app.bookmarks = vec![Preset::at(1, 1, 1)];
+        press(&mut app, KeyCode::Char('P'), KeyModifiers::SHIFT, 1);
+        press(&mut app, KeyCode::Char('d'), KeyModifiers::NONE, 1);
+        assert!(app.status_message.contains("Not saved") && app.status_message.contains("failed to load"), "{}", app.status_message);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[[bookmark]]\npan = \"oops\"\n");
+        press(&mut app, KeyCode::Esc, KeyModifiers::NONE, 1);
+
+        press(&mut app, KeyCode::Char('b'), KeyModifiers::NONE, 1);
+        let error = app.cleanup().unwrap_err().to_string();
+        assert!(error.contains("failed to load"), "{}", error);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[[bookmark]]\npan = \"oops\"\n");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn system_presets_come_from_the_given_path() {
+        let paths = AppPaths::scratch();
+        std::fs::write(&paths.system_presets, "[[bookmark]]\npan = 1\ntilt = 2\nzoom = 3\nlabel = \"wide\"\n").unwrap();
+        std::fs::write(&paths.presets, "[[bookmark]]\npan = 4\ntilt = 5\nzoom = 6\n").unwrap();
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(FakeCamera::new()), paths.clone());
+        app.command_interval = Duration::ZERO;
+        let _ = std::fs::remove_file(&paths.system_presets);
+        let _ = std::fs::remove_file(&paths.presets);
+        assert_eq!(app.bookmarks().iter().map(|b| (b.pan, b.system)).collect::<Vec<_>>(), [(1, true), (4, false)]);
+
+        press(&mut app, KeyCode::Char('P'), KeyModifiers::SHIFT, 1);
+        press(&mut app, KeyCode::Char('d'), KeyModifiers::NONE, 1);
+        assert!(app.status_message.contains(&paths.system_presets.display().to_string()), "{}", app.status_message);
+    }
+
+    #[test]
+    fn presets_list_deletes_and_overwrites_and_saves_at_once() {
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(FakeCamera::new()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        let path = std::env::temp_dir().join(format!("tiny-ptz-presets-panel-{}.toml", std::process::id()));
+        app.presets_store = PresetsStore::File(path.clone());
+        app.bookmarks = vec![
+            Preset { system: true, label: Some("wide".to_string()), ..Preset::at(0, 0, 0) },
+            Preset::at(1, 1, 1),
+            Preset::at(2, 2, 2),
+        ];
+        press(&mut app, KeyCode::Char('P'), KeyModifiers::SHIFT, 1);
+        assert_eq!(app.presets_panel(), Some(0));
+
+        // System presets are read-only
+        press(&mut app, KeyCode::Char('d'), KeyModifiers::NONE, 1);
+        assert_eq!(app.bookmarks().len(), 3);
+        assert!(app.status_message.contains("system preset"), "{}", app.status_message);
+
+        press(&mut app, KeyCode::Down, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Char('d'), KeyModifiers::NONE, 1);
+        assert_eq!(app.bookmarks().iter().map(|b| b.pan).collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(presets::load_bookmarks(&path).unwrap(), [Preset::at(2, 2, 2)]);
+
+        // Arrows select within the list instead of moving the camera; Enter stores the position
+        press(&mut app, KeyCode::Down, KeyModifiers::NONE, 3);
+        assert_eq!((app.presets_panel(), app.get_tilt()), (Some(1), 0));
+        press(&mut app, KeyCode::Esc, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Char('P'), KeyModifiers::SHIFT, 1);
+        press(&mut app, KeyCode::Down, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE, 1);
+        let pan = app.get_pan();
+        assert_eq!(presets::load_bookmarks(&path).unwrap(), [Preset::at(pan, 0, 0)]);
+        assert!(!app.bookmarks_changed);
+
+        // With confirmation on, the first Enter only asks
+        app.camera_controller.config.confirm_preset_overwrite = true;
+        press(&mut app, KeyCode::Esc, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Char('P'), KeyModifiers::SHIFT, 1);
+        press(&mut app, KeyCode::Down, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE, 1);
+        assert_eq!(app.status_message, "Slot 2 occupied — press Enter again to overwrite.");
+        assert_eq!(presets::load_bookmarks(&path).unwrap(), [Preset::at(pan, 0, 0)]);
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE, 1);
+        assert_eq!(presets::load_bookmarks(&path).unwrap(), [Preset::at(app.get_pan(), 0, 0)]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn bookmarks_glide_with_pan_and_tilt_arriving_together() {
+        let camera = FakeCamera::new();
+        let config: CameraConfig = toml::from_str("[movement]\nsmooth_move_ms = 1000").unwrap();
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        app.bookmarks = vec![Preset::at(400000, -40000, 0)];
+        press(&mut app, KeyCode::Char('n'), KeyModifiers::NONE, 1);
+        assert!(app.smooth_move.is_some());
+        assert_eq!(camera.value("pan_absolute"), Some(0));
+
+        let start = Instant::now();
+        app.smooth_move = Some(SmoothMove::new([0; 3], [400000, -40000, 0], start, Duration::from_secs(1)));
+        app.advance_smooth_move(start + Duration::from_millis(500));
+        assert_eq!(["pan_absolute", "tilt_absolute"].map(|name| camera.value(name)), [Some(200000), Some(-20000)]);
+        app.advance_smooth_move(start + Duration::from_secs(2));
+        assert_eq!(["pan_absolute", "tilt_absolute"].map(|name| camera.value(name)), [Some(400000), Some(-40000)]);
+        assert!(app.smooth_move.is_none());
+
+        // A key press takes over from a glide in progress
+        app.smooth_move = Some(SmoothMove::new([0; 3], [0; 3], start, Duration::from_secs(1)));
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE, 1);
+        assert!(app.smooth_move.is_none());
+    }
+
+    #[test]
+    fn verified_recalls_retry_once_and_report_the_miss() {
+        let camera = FakeCamera::new().with_control("pan_absolute", -468000, 100000, 0).dropping_writes(1);
+        let config = CameraConfig { verify_presets: true, ..CameraConfig::default() };
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        app.bookmarks = vec![Preset::at(36000, 3600, 5), Preset::at(400000, 0, 0)];
+
+        // The first write is lost; the retry gets there
+        press(&mut app, KeyCode::Char('n'), KeyModifiers::NONE, 1);
+        assert_eq!(app.status_message, "Bookmark 1/2 (personal); preset 1 reached.");
+        assert_eq!(camera.value("pan_absolute"), Some(36000));
+
+        // Pan stops short at the camera's own limit, even after the retry
+        press(&mut app, KeyCode::Char('n'), KeyModifiers::NONE, 1);
+        assert_eq!(app.status_message, "Bookmark 2/2 (personal); preset 2 failed (pan off by -300000).");
+        assert_eq!(app.get_pan(), 100000);
+    }
+
+    #[test]
+    fn image_control_keys_step_from_the_device_value() {
+        let camera = FakeCamera::new().with_control("brightness", 0, 255, 250);
+        let config: CameraConfig =
+            toml::from_str("[keys.image.brightness]\nincrease = \"B\"\ndecrease = \"Ctrl+b\"\nstep = 8").unwrap();
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        press(&mut app, KeyCode::Char('b'), KeyModifiers::CONTROL, 2);
+        assert_eq!(camera.value("brightness"), Some(234));
+        assert_eq!(app.status_message, "brightness: 234");
+        press(&mut app, KeyCode::Char('B'), KeyModifiers::SHIFT, 3);
+        assert_eq!(camera.value("brightness"), Some(255));
+
+        let clashing: CameraConfig = toml::from_str("[hooks]\nB = \"true\"\n[keys.image.brightness]\nincrease = \"B\"").unwrap();
+        assert!(clashing.validate().unwrap_err().to_string().contains("image control brightness"));
+    }
+
+    #[test]
+    fn locked_camera_ignores_moves_but_not_unlock_or_quit() {
+        let camera = FakeCamera::new();
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        app.bookmarks = vec![Preset::at(36000, 0, 0)];
+        press(&mut app, KeyCode::Char('l'), KeyModifiers::NONE, 1);
+        assert!(app.locked());
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Up, KeyModifiers::SHIFT, 1);
+        press(&mut app, KeyCode::Char('n'), KeyModifiers::NONE, 1);
+        app.centre_on(1000, 1000);
+        assert!(camera.commands().iter().all(|args| !args.contains(&"--set-ctrl".to_string())));
+        assert!(app.status_message.starts_with("Locked"), "{}", app.status_message);
+
+        press(&mut app, KeyCode::Char('l'), KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Char('n'), KeyModifiers::NONE, 1);
+        assert_eq!(camera.value("pan_absolute"), Some(36000));
+        press(&mut app, KeyCode::Char('l'), KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Char('q'), KeyModifiers::NONE, 1);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn locking_stops_a_glide_and_held_back_moves() {
+        let camera = FakeCamera::new();
+        let config: CameraConfig = toml::from_str("[movement]\nsmooth_move_ms = 1000").unwrap();
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        let start = Instant::now();
+        app.smooth_move = Some(SmoothMove::new([0; 3], [400000, 0, 0], start, Duration::from_secs(1)));
+        app.advance_smooth_move(start + Duration::from_millis(500));
+        assert_eq!(camera.value("pan_absolute"), Some(200000));
+        app.camera_controller.stage(Axis::Tilt, 36000);
+        app.last_staged = Some((Action::TiltUp, start));
+
+        press(&mut app, KeyCode::Char('l'), KeyModifiers::NONE, 1);
+        app.update(InputEvent::Tick);
+        assert!(app.smooth_move.is_none());
+        assert_eq!(["pan_absolute", "tilt_absolute"].map(|name| camera.value(name)), [Some(200000), Some(0)]);
+        assert_eq!((app.get_pan(), app.get_tilt()), (200000, 0));
+    }
+
+    #[test]
+    fn peeking_shows_the_next_bookmark_without_moving() {
+        let camera = FakeCamera::new();
+        let mut config = CameraConfig::default();
+        config.pan.units_per_degree = Some(3600.0);
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        app.bookmarks = vec![Preset { label: Some("desk".to_string()), ..Preset::at(36000, 0, 20) }, Preset::at(0, 0, 0)];
+        press(&mut app, KeyCode::Char('N'), KeyModifiers::SHIFT, 2);
+        assert_eq!(
+            app.status_message,
+            "Next is bookmark 1/2 (personal): desk: pan 36000 (54%, 10.0°), tilt 0 (50%), zoom 20 (20%). Press 'n' to go there."
+        );
+        assert_eq!(camera.value("pan_absolute"), Some(0));
+        press(&mut app, KeyCode::Char('n'), KeyModifiers::NONE, 1);
+        assert_eq!(camera.value("pan_absolute"), Some(36000));
+        press(&mut app, KeyCode::Char('N'), KeyModifiers::SHIFT, 1);
+        assert!(app.status_message.starts_with("Next is bookmark 2/2"), "{}", app.status_message);
+    }
+
+    #[test]
+    fn arrow_zoom_mode_sends_up_and_down_to_zoom() {
+        let camera = FakeCamera::new();
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        press(&mut app, KeyCode::Char('z'), KeyModifiers::NONE, 1);
+        assert!(app.arrows_zoom());
+        press(&mut app, KeyCode::Up, KeyModifiers::NONE, 2);
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        assert_eq!(
+            ["pan_absolute", "tilt_absolute", "zoom_absolute"].map(|name| camera.value(name)),
+            [Some(app.get_pan()), Some(0), Some(20)]
+        );
+        assert!(app.get_pan() > 0);
+
+        press(&mut app, KeyCode::Char('z'), KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Down, KeyModifiers::NONE, 1);
+        assert!(camera.value("tilt_absolute").unwrap() < 0);
+        assert_eq!(camera.value("zoom_absolute"), Some(20));
+    }
+
+    #[test]
+    fn enter_repeats_the_last_step_move() {
+        let camera = FakeCamera::new();
+        let mut config = CameraConfig::default();
+        config.movement.zoom_adjust = false;
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE, 1);
+        assert!(camera.commands().iter().all(|args| !args.contains(&"--set-ctrl".to_string())));
+
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE, 2);
+        assert_eq!(camera.value("pan_absolute"), Some(-90000));
+        press(&mut app, KeyCode::Char('b'), KeyModifiers::NONE, 1); // Not a move, so not what Enter repeats
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE, 1);
+        assert_eq!(camera.value("pan_absolute"), Some(-120000));
+    }
+
+    #[test]
+    fn stopping_the_preview_does_not_wait_for_the_player() {
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(FakeCamera::new()), AppPaths::scratch());
+        // A player that ignores SIGTERM, so only the deadline gets rid of it
+        let player = Command::new("sh").args(["-c", "trap '' TERM; sleep 5"]).spawn().unwrap();
+        app.video_feed = Some(player);
+        let start = Instant::now();
+        app.stop_video_feed();
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert!(!app.preview_running() && app.stopping_player.is_some());
+
+        // A new preview waits for the old player to go
+        app.toggle_video_feed();
+        assert_eq!(app.status_message, "Video feed starts once the previous player has exited.");
+        app.reap_stopping_player(Instant::now());
+        assert!(app.stopping_player.is_some());
+        app.reap_stopping_player(Instant::now() + Duration::from_secs(2));
+        assert!(app.stopping_player.is_none());
+        app.update(InputEvent::Tick);
+        assert!(app.video_retry.is_none());
+    }
+
+    #[test]
+    fn preview_is_relaunched_when_the_device_reappears() {
+        let device = std::env::temp_dir().join(format!("tiny-ptz-video-{}", std::process::id()));
+        let _ = std::fs::remove_file(&device);
+        let camera = FakeCamera::new();
+        let mut config = CameraConfig { device: device.display().to_string(), ..CameraConfig::default() };
+        config.video.auto_relaunch = true;
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        let launches = || camera.commands().iter().filter(|args| args[0] == "ffplay").count();
+
+        // Missing at startup, so turning up isn't a reconnection
+        let status = app.status_message.clone();
+        std::fs::write(&device, "").unwrap();
+        app.update(InputEvent::Tick);
+        assert_eq!(app.status_message, status);
+
+        std::fs::remove_file(&device).unwrap();
+        app.update(InputEvent::Tick);
+        assert_eq!(app.status_message, format!("Camera {} disconnected; waiting for it to reappear.", device.display()));
+        // Stand-in for a running player, which the fake camera can't start
+        app.preview_was_running = true;
+
+        std::fs::write(&device, "").unwrap();
+        app.update(InputEvent::Tick);
+        let relaunching = format!("Camera {} reconnected; relaunching the preview. ", device.display());
+        assert!(app.status_message.starts_with(&relaunching), "{}", app.status_message);
+        assert_eq!(launches(), 1);
+        assert!(!app.preview_was_running);
+
+        // Without a preview running at the unplug, nothing is started
+        std::fs::remove_file(&device).unwrap();
+        app.update(InputEvent::Tick);
+        std::fs::write(&device, "").unwrap();
+        app.update(InputEvent::Tick);
+        assert_eq!(app.status_message, format!("Camera {} reconnected.", device.display()));
+        assert_eq!(launches(), 1);
+        let _ = std::fs::remove_file(&device);
+    }
+
+    #[test]
+    fn zoom_stops_cycle_from_the_current_zoom() {
+        let camera = FakeCamera::new().with_control("zoom_absolute", 0, 100, 30);
+        let mut config = CameraConfig::default();
+        config.zoom.stops = vec![10, 50, 90];
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        app.camera_controller.sync_from_device().unwrap();
+        app.command_interval = Duration::ZERO;
+        assert_eq!(app.zoom_stop(), None);
+
+        press(&mut app, KeyCode::Char('Z'), KeyModifiers::NONE, 1);
+        assert_eq!((camera.value("zoom_absolute"), app.zoom_stop()), (Some(50), Some(1)));
+        press(&mut app, KeyCode::Char('Z'), KeyModifiers::NONE, 2);
+        assert_eq!((camera.value("zoom_absolute"), app.zoom_stop()), (Some(10), Some(0)));
+        assert_eq!(app.status_message, "Zoom stop 1/3 (10)");
+        assert!(camera.commands().last().unwrap().ends_with(&["zoom_absolute=10".to_string()]));
+
+        press(&mut app, KeyCode::Up, KeyModifiers::SHIFT, 1);
+        assert_eq!(app.zoom_stop(), None);
+    }
+
+    #[test]
+    fn backspace_swaps_between_the_last_two_framings() {
+        let camera = FakeCamera::new();
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        press(&mut app, KeyCode::Backspace, KeyModifiers::NONE, 1);
+        assert!(app.status_message.starts_with("No previous position"), "{}", app.status_message);
+
+        app.bookmarks = vec![Preset::at(36000, -3600, 20)];
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        let start = [app.get_pan(), app.get_tilt(), app.get_zoom()];
+        press(&mut app, KeyCode::Char('n'), KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Backspace, KeyModifiers::NONE, 1);
+        assert_eq!([app.get_pan(), app.get_tilt(), app.get_zoom()], start);
+        press(&mut app, KeyCode::Backspace, KeyModifiers::NONE, 1);
+        assert_eq!([app.get_pan(), app.get_tilt(), app.get_zoom()], [36000, -3600, 20]);
+
+        // Step moves don't replace the remembered position; jumps to a limit do
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE, 1);
+        press(&mut app, KeyCode::Backspace, KeyModifiers::NONE, 1);
+        assert_eq!(app.get_pan(), start[0]);
+        press(&mut app, KeyCode::Up, KeyModifiers::ALT, 1);
+        press(&mut app, KeyCode::Backspace, KeyModifiers::NONE, 1);
+        assert_eq!(app.get_tilt(), start[1]);
+        assert_eq!(camera.value("pan_absolute"), Some(start[0]));
+    }
+
+    #[test]
+    fn clicks_aim_the_camera_unless_the_presets_list_is_open() {
+        let camera = FakeCamera::new();
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        app.set_panel_areas([Rect::new(0, 0, 11, 5), Rect::new(11, 0, 11, 5), Rect::default()]);
+        let click = |column, row| {
+            InputEvent::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+
+        app.update(click(0, 0));
+        assert_eq!((app.get_pan(), app.get_tilt()), (-468000, 324000));
+        assert_eq!(app.status_message, "Aimed at pan -468000, tilt 324000.");
+
+        press(&mut app, KeyCode::Char('P'), KeyModifiers::SHIFT, 1);
+        let sent = camera.commands().len();
+        app.update(click(21, 4));
+        assert_eq!(camera.commands().len(), sent);
+        assert_eq!((app.get_pan(), app.get_tilt()), (-468000, 324000));
+    }
+
+    #[test]
+    fn quit_without_saving_discards_the_session() {
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(FakeCamera::new()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        press(&mut app, KeyCode::Char('b'), KeyModifiers::NONE, 1);
+        assert!(app.bookmarks_changed);
+        press(&mut app, KeyCode::Char('Q'), KeyModifiers::SHIFT, 1);
+        assert!(app.should_quit && !app.save_on_quit);
+        app.cycle_theme();
+        app.cleanup().unwrap();
+        // Nothing is written
+        assert!(!app.paths.presets.exists() && !app.paths.state.exists());
+    }
+
+    #[test]
+    fn debounced_moves_go_out_after_a_pause_or_a_large_change() {
+        let camera = FakeCamera::new();
+        let config: CameraConfig = toml::from_str("[pan]\nstep = 10\n[movement]\ndebounce_ms = 20\ndebounce_steps = 2").unwrap();
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        let sets = || camera.commands().into_iter().filter(|args| args.contains(&"--set-ctrl".to_string())).count();
+
+        // Two steps are within the limit and wait; the third goes out at once, as one command
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 2);
+        app.update(InputEvent::Tick);
+        assert_eq!((sets(), app.get_pan()), (0, 20));
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        assert_eq!((sets(), camera.value("pan_absolute")), (1, Some(30)));
+
+        // A small move goes out once keys stop arriving
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE, 1);
+        assert_eq!(sets(), 1);
+        thread::sleep(Duration::from_millis(25));
+        app.update(InputEvent::Tick);
+        assert_eq!((sets(), camera.value("pan_absolute")), (2, Some(20)));
+    }
+
+    #[test]
+    fn plain_mode_spells_out_moves() {
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(FakeCamera::new()), AppPaths::scratch());
+        app.plain = true;
+        app.command_interval = Duration::ZERO;
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE, 1);
+        assert_eq!(app.status_message, "Pan increased to 30000, 53 percent");
+        press(&mut app, KeyCode::Down, KeyModifiers::SHIFT, 1);
+        assert_eq!(app.status_message, "Zoom unchanged at 0, 0 percent");
+    }
+
+    #[test]
+    fn startup_position_moves_only_the_given_axes() {
+        let camera = FakeCamera::new().with_control("tilt_absolute", -324000, 324000, 3600);
+        let config: CameraConfig = toml::from_str("[startup_position]\npan = 36000\nzoom = 30").unwrap();
+        let app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        assert_eq!(app.status_message, "Moved to the startup position.");
+        assert_eq!(
+            ["pan_absolute", "tilt_absolute", "zoom_absolute"].map(|name| camera.value(name)),
+            [Some(36000), Some(3600), Some(30)]
+        );
+        let sets: Vec<_> = camera.commands().into_iter().filter(|args| args.contains(&"--set-ctrl".to_string())).collect();
+        assert_eq!(sets.len(), 1);
+    }
+
+    #[test]
+    fn missing_axis_is_disabled_without_sending_commands() {
+        let camera = FakeCamera::new().without_control("tilt_absolute");
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(camera.clone()), AppPaths::scratch());
+        app.command_interval = Duration::ZERO;
+        assert!(app.status_message.starts_with("Tilt not supported"), "{}", app.status_message);
+
+        let before = camera.commands().len();
+        press(&mut app, KeyCode::Up, KeyModifiers::NONE, 3);
+        assert_eq!(camera.commands().len(), before);
+    }
+
+    #[test]
+    fn hook_keys_run_through_the_executor() {
+        let camera = FakeCamera::new();
+        let config: CameraConfig = toml::from_str("[hooks]\n\"Ctrl+l\" = \"lights toggle\"").unwrap();
+        config.validate().unwrap();
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+
+        press(&mut app, KeyCode::Char('l'), KeyModifiers::CONTROL, 1);
+        for _ in 0..100 {
+            app.update(InputEvent::Tick);
+            if app.status_message.starts_with("Hook finished") {
+                break;
+            }
+            thread::sleep(Duration::from_millis(5));
         }
+        assert_eq!(app.status_message, "Hook finished: lights toggle");
+        assert!(camera.commands().contains(&vec!["sh".to_string(), "-c".to_string(), "lights toggle".to_string()]));
+
+        // A hook takes a key over from its default action, but not from one set in [keys]
+        let config: CameraConfig = toml::from_str("[hooks]\nEnter = \"scene 2\"").unwrap();
+        config.validate().unwrap();
+        let mut app = App::with_paths(config, Arc::new(camera.clone()), AppPaths::scratch());
+        assert_eq!(app.keybindings().key_for(Action::RepeatLast), None);
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE, 1);
+        assert_eq!(app.status_message, "Running hook: scene 2");
+        let clashing: CameraConfig = toml::from_str("[keys]\nrepeat_last = \"Enter\"\n[hooks]\nEnter = \"true\"").unwrap();
+        assert!(clashing.validate().is_err());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,46 @@
+// src/backend.rs
+use std::io;
+use std::process::{Child, Command, Stdio};
+
+/// What one `v4l2-ctl` invocation produced
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CtlOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Runs `v4l2-ctl` with the given arguments. Every device access goes through this,
+/// so tests can swap the real binary for an in-memory camera.
+pub trait Executor: Send + Sync {
+    fn run(&self, args: &[String]) -> io::Result<CtlOutput>;
+
+    /// Runs a `[hooks]` command line through `sh -c`
+    fn shell(&self, command: &str) -> io::Result<CtlOutput> {
+        capture(Command::new("sh").arg("-c").arg(command))
+    }
+
+    /// Starts the `ffplay` preview with `args`, its error output going to `stderr`
+    fn spawn_player(&self, args: &[String], stderr: Stdio) -> io::Result<Child> {
+        Command::new("ffplay").args(args).stdin(Stdio::null()).stdout(Stdio::null()).stderr(stderr).spawn()
+    }
+}
+
+fn capture(command: &mut Command) -> io::Result<CtlOutput> {
+    let output = command.output()?;
+    Ok(CtlOutput {
+        success: output.status.success(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+/// The `v4l2-ctl` binary on `PATH`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct V4l2Ctl;
+
+impl Executor for V4l2Ctl {
+    fn run(&self, args: &[String]) -> io::Result<CtlOutput> {
+        capture(Command::new("v4l2-ctl").args(args))
+    }
+}
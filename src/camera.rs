@@ -1,23 +1,1048 @@
 // src/camera.rs
-use std::process::Command;
-use anyhow::{Result, bail};
-use serde::Deserialize;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 
-#[derive(Debug, Deserialize, Clone)]
+use crate::backend::{CtlOutput, Executor, V4l2Ctl};
+#[cfg(feature = "tui")]
+use crate::keys::{Hooks, KeyBindings};
+use crate::presets::Preset;
+#[cfg(feature = "tui")]
+use crate::ui::{DisplayConfig, UiConfig};
+
+/// Without the TUI, `[ui]`, `[display]`, `[keys]` and `[hooks]` are kept as written, so the
+/// same config files load and `--print-config` passes them through, but nothing reads them
+#[cfg(not(feature = "tui"))]
+type UiConfig = toml::Table;
+#[cfg(not(feature = "tui"))]
+type DisplayConfig = toml::Table;
+#[cfg(not(feature = "tui"))]
+type KeyBindings = toml::Table;
+#[cfg(not(feature = "tui"))]
+type Hooks = toml::Table;
+
+/// Limits and step size for one axis. Every field is optional in `config.toml`;
+/// missing ones take the values from `ControlConfig::default()`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
 pub struct ControlConfig {
     pub min: i32,
     pub max: i32,
+    /// Units per key press; `config.toml` may also give it as a share of the range,
+    /// `step = "1%"`, which is resolved against `min`/`max` when the config is read
     pub step: i32,
+    /// Reverse the direction key presses move this axis
+    pub invert: bool,
+    /// Draw the gauge reversed (100% at `min`, 0% at `max`); control values are unaffected
+    pub inverted_display: bool,
+    /// Value `reset_to_defaults` (key `R`) returns this axis to; unset axes are left alone
+    pub default: Option<i32>,
+    /// Zoom only: cap on the units moved per key press
+    pub max_step: Option<i32>,
+    /// Zoom only: move this percentage of the range per key press (e.g. 5.0) instead of `step`.
+    /// Unlike `step = "N%"`, which becomes fixed units when the config is read, it wins over
+    /// `step` and any `[profile]` zoom step.
+    pub percent_step: Option<f64>,
+    /// Zoom only: levels the quick-zoom key cycles through, e.g. `[10, 50, 90]` for wide,
+    /// medium and tight
+    pub stops: Vec<i32>,
+    /// Zoom only: fraction of the range at each end (e.g. 0.2) in which steps slow down
+    /// linearly toward the limit, bottoming out at a quarter of the step
+    pub edge_ramp: Option<f64>,
+    /// Zoom only: `[zoom.display]`, labelling the gauge with an optical multiplier ("2.3x")
+    /// instead of a percentage
+    pub display: Option<ZoomDisplay>,
+    /// Control units per degree of rotation (3600 for UVC arc-second units), used for degree readouts
+    pub units_per_degree: Option<f64>,
+    /// The device control driving this axis, for cameras that don't use `pan_absolute` and
+    /// friends. Either v4l2-ctl's name or the driver's own (`Absolute Pan`) works.
+    pub control: Option<String>,
+    /// Key-press moves smaller than this many units are dropped, for cameras that click on
+    /// every write; 0 sends every change
+    pub deadband: i32,
+    /// Set to false for cameras without this axis: its keys do nothing and its panel is hidden.
+    /// Also cleared at startup when `--list-ctrls` doesn't report the control.
+    pub supported: bool,
+}
+
+/// Whether any `[axis]` of `table` has a `step = "N%"` for `resolve_percent_step`
+fn has_percent_step(table: &toml::Table) -> bool {
+    Axis::ALL.iter().any(|axis| table.get(axis.name()).and_then(|section| section.get("step")).is_some_and(toml::Value::is_str))
+}
+
+/// Turns a `step = "N%"` in the `[axis]` section of `table` into units of that section's
+/// range, once includes are merged so `min` and `max` may come from either file. The units
+/// are fixed from then on, like any `step`; `zoom.percent_step` is the per-press alternative.
+fn resolve_percent_step(table: &mut toml::Table, axis: &str) -> Result<()> {
+    let Some(toml::Value::Table(section)) = table.get_mut(axis) else {
+        return Ok(());
+    };
+    let Some(toml::Value::String(step)) = section.get("step") else {
+        return Ok(());
+    };
+    let defaults = ControlConfig::default();
+    let limit = |key: &str, default: i32| section.get(key).and_then(toml::Value::as_integer).unwrap_or(default as i64);
+    let units = step_from_percent(step, limit("min", defaults.min), limit("max", defaults.max))
+        .with_context(|| format!("In [{}]", axis))?;
+    section.insert("step".to_string(), toml::Value::Integer(units.into()));
+    Ok(())
+}
+
+/// A `step = "N%"` as units: N percent of the range from `min` to `max`, rounded.
+/// Shares that round to nothing are rejected rather than becoming a step of 0.
+pub fn step_from_percent(step: &str, min: i64, max: i64) -> Result<i32> {
+    let Some(percent) = step.trim().strip_suffix('%').and_then(|n| n.trim().parse::<f64>().ok()) else {
+        bail!("step must be a number of units or a percentage of the range like \"1%\", got \"{}\"", step);
+    };
+    let units = ((max - min) as f64 * percent / 100.0).round();
+    if !(units >= 1.0 && units <= i32::MAX as f64) {
+        bail!("step \"{}\" of the range {}..={} comes to {} units; it must be at least 1", step, min, max, units);
+    }
+    Ok(units as i32)
+}
+
+impl Default for ControlConfig {
+    /// A 0–100 range moved one unit at a time, not inverted
+    fn default() -> Self {
+        ControlConfig {
+            min: 0,
+            max: 100,
+            step: 1,
+            invert: false,
+            inverted_display: false,
+            default: None,
+            max_step: None,
+            percent_step: None,
+            stops: Vec::new(),
+            edge_ramp: None,
+            display: None,
+            units_per_degree: None,
+            control: None,
+            deadband: 0,
+            supported: true,
+        }
+    }
+}
+
+impl ControlConfig {
+    /// Apply the axis `invert` flag to a key-driven delta
+    pub fn directed(&self, delta: i32) -> i32 {
+        if self.invert { -delta } else { delta }
+    }
+
+    /// Units one key press asks for: `percent_step` of the range when set, otherwise `step`
+    pub fn key_step(&self) -> i32 {
+        match self.percent_step {
+            Some(percent) => ((self.max as f64 - self.min as f64) * percent / 100.0).round().max(1.0) as i32,
+            None => self.step,
+        }
+    }
+
+    /// Where `value` sits between `min` (0) and `max` (100), clamped to that range
+    pub fn percent(&self, value: i32) -> f64 {
+        let range = (self.max as f64 - self.min as f64).max(1.0);
+        ((value as f64 - self.min as f64) / range * 100.0).clamp(0.0, 100.0)
+    }
+
+    /// Rejects limits the controller can't work with, naming the offending `[axis]`
+    pub fn validate(&self, axis: &str) -> Result<()> {
+        if self.min >= self.max {
+            bail!("[{}] min ({}) must be less than max ({})", axis, self.min, self.max);
+        }
+        if self.step <= 0 {
+            bail!("[{}] step must be positive, got {}", axis, self.step);
+        }
+        if let Some(default) = self.default.filter(|d| !(self.min..=self.max).contains(d)) {
+            bail!("[{}] default ({}) must be between min ({}) and max ({})", axis, default, self.min, self.max);
+        }
+        if let Some(cap) = self.max_step.filter(|cap| *cap <= 0) {
+            bail!("[{}] max_step must be positive, got {}", axis, cap);
+        }
+        if let Some(percent) = self.percent_step.filter(|percent| !(*percent > 0.0 && *percent <= 100.0)) {
+            bail!("[{}] percent_step must be above 0 and at most 100, got {}", axis, percent);
+        }
+        if let Some(stop) = self.stops.iter().find(|stop| !(self.min..=self.max).contains(*stop)) {
+            bail!("[{}] stops must be between min ({}) and max ({}), got {}", axis, self.min, self.max, stop);
+        }
+        if let Some(ramp) = self.edge_ramp.filter(|ramp| !(0.0..=0.5).contains(ramp)) {
+            bail!("[{}] edge_ramp must be between 0.0 and 0.5, got {}", axis, ramp);
+        }
+        if let Some(units) = self.units_per_degree.filter(|units| *units <= 0.0) {
+            bail!("[{}] units_per_degree must be positive, got {}", axis, units);
+        }
+        if self.control.as_deref().is_some_and(|name| v4l2_control_name(name).is_empty()) {
+            bail!("[{}] control must contain a letter or digit", axis);
+        }
+        if self.deadband < 0 {
+            bail!("[{}] deadband can't be negative, got {}", axis, self.deadband);
+        }
+        if let Some(display) = &self.display {
+            display.validate().with_context(|| format!("In [{}.display]", axis))?;
+        }
+        Ok(())
+    }
+}
+
+/// The `[zoom.display]` section: `table` maps control values to optical multipliers,
+/// e.g. `[[0, 1.0], [50, 2.3], [100, 5.0]]`. Values between points are interpolated
+/// linearly; values beyond either end take that end's multiplier.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ZoomDisplay {
+    pub table: Vec<(i32, f64)>,
+}
+
+impl ZoomDisplay {
+    /// The multiplier at control value `value`, `None` for an empty table
+    pub fn multiplier(&self, value: i32) -> Option<f64> {
+        let (first, last) = (self.table.first()?, self.table.last()?);
+        if value <= first.0 {
+            return Some(first.1);
+        }
+        if value >= last.0 {
+            return Some(last.1);
+        }
+        let upper = self.table.iter().position(|point| point.0 >= value)?;
+        let ((x0, y0), (x1, y1)) = (self.table[upper - 1], self.table[upper]);
+        Some(y0 + (y1 - y0) * (value - x0) as f64 / (x1 - x0) as f64)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.table.is_empty() {
+            bail!("table needs at least one [value, multiplier] point");
+        }
+        if let Some(pair) = self.table.windows(2).find(|pair| pair[0].0 >= pair[1].0) {
+            bail!("table values must increase, but {} is followed by {}", pair[0].0, pair[1].0);
+        }
+        if let Some((_, multiplier)) = self.table.iter().find(|(_, multiplier)| !(multiplier.is_finite() && *multiplier > 0.0)) {
+            bail!("table multipliers must be positive, got {}", multiplier);
+        }
+        Ok(())
+    }
+}
+
+/// The `[movement]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MovementConfig {
+    /// Scale pan/tilt steps down as the camera zooms in; false gives the same step at every zoom
+    pub zoom_adjust: bool,
+    /// Hold step moves that arrive within the command interval and send them together, one
+    /// per axis, once it elapses; false drops them as before
+    pub queue_moves: bool,
+    /// Shift+Up zooms in and Shift+Down out; false swaps them. Only the default zoom keys
+    /// are affected, and `zoom.invert` still applies on top.
+    pub shift_up_zooms_in: bool,
+    /// Hold arrow/zoom moves until no key has arrived for this long, then send the latest
+    /// targets in one command; unset sends each move as it comes
+    pub debounce_ms: Option<u64>,
+    /// With `debounce_ms`: send straight away once an axis is more than this many steps
+    /// from where it was last sent
+    pub debounce_steps: u32,
+    /// Glide to recalled bookmarks over this long, every axis arriving at the same moment;
+    /// unset jumps straight there
+    pub smooth_move_ms: Option<u64>,
+    /// Set the command interval from how long recent `v4l2-ctl` calls took, a little above
+    /// their average, instead of from the config, the motion profile or `[`/`]`
+    pub adaptive_interval: bool,
+    /// With `adaptive_interval`: the shortest interval it will choose
+    pub adaptive_min_ms: u64,
+    /// With `adaptive_interval`: the longest interval it will choose
+    pub adaptive_max_ms: u64,
+}
+
+/// How far above the average call latency the adaptive interval sits
+const ADAPTIVE_HEADROOM: f64 = 1.2;
+
+impl MovementConfig {
+    /// With `adaptive_interval`: the interval for the calls measured so far, `None` before
+    /// the first call or when the interval isn't adaptive
+    pub fn adaptive_interval(&self, latency: &CommandLatency) -> Option<Duration> {
+        if !self.adaptive_interval {
+            return None;
+        }
+        let average = latency.average()?.mul_f64(ADAPTIVE_HEADROOM);
+        Some(average.clamp(Duration::from_millis(self.adaptive_min_ms), Duration::from_millis(self.adaptive_max_ms)))
+    }
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        MovementConfig {
+            zoom_adjust: true,
+            queue_moves: true,
+            shift_up_zooms_in: true,
+            debounce_ms: None,
+            debounce_steps: 3,
+            smooth_move_ms: None,
+            adaptive_interval: false,
+            adaptive_min_ms: 25,
+            adaptive_max_ms: 500,
+        }
+    }
+}
+
+/// A glide from one position to another over `duration`. Each axis covers the same
+/// fraction of its own distance at any moment, so a long pan and a short tilt finish together.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothMove {
+    from: [i32; 3],
+    to: [i32; 3],
+    started: Instant,
+    duration: Duration,
+}
+
+impl SmoothMove {
+    pub fn new(from: [i32; 3], to: [i32; 3], started: Instant, duration: Duration) -> Self {
+        SmoothMove { from, to, started, duration }
+    }
+
+    /// Where every axis should be at `now`, and whether that is the target
+    pub fn position_at(&self, now: Instant) -> ([i32; 3], bool) {
+        let elapsed = now.saturating_duration_since(self.started);
+        if elapsed >= self.duration {
+            return (self.to, true);
+        }
+        let fraction = elapsed.as_secs_f64() / self.duration.as_secs_f64();
+        let position = std::array::from_fn(|axis| {
+            let (from, to) = (self.from[axis] as f64, self.to[axis] as f64);
+            (from + (to - from) * fraction).round() as i32
+        });
+        (position, false)
+    }
+}
+
+/// The `[metrics]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// Address the `/metrics` endpoint listens on
+    pub listen: String,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig { listen: "127.0.0.1:9898".to_string() }
+    }
+}
+
+/// A `[profile.<name>]` section: movement settings laid over the base config while the
+/// profile is active. Fields left out keep the base value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MotionProfile {
+    pub pan_step: Option<i32>,
+    pub tilt_step: Option<i32>,
+    pub zoom_step: Option<i32>,
+    /// In place of `[zoom] max_step` and `edge_ramp`
+    pub zoom_max_step: Option<i32>,
+    pub zoom_edge_ramp: Option<f64>,
+    /// In place of `[movement] zoom_adjust`
+    pub zoom_adjust: Option<bool>,
+    /// Minimum time between commands, in place of the built-in interval
+    pub command_interval_ms: Option<u64>,
+}
+
+impl MotionProfile {
+    /// The base settings of `config`, for switching back from a profile with `restore`
+    pub fn capture(config: &CameraConfig, command_interval_ms: u64) -> Self {
+        MotionProfile {
+            pan_step: Some(config.pan.step),
+            tilt_step: Some(config.tilt.step),
+            zoom_step: Some(config.zoom.step),
+            zoom_max_step: config.zoom.max_step,
+            zoom_edge_ramp: config.zoom.edge_ramp,
+            zoom_adjust: Some(config.movement.zoom_adjust),
+            command_interval_ms: Some(command_interval_ms),
+        }
+    }
+
+    /// Overlays the profile's settings on `config`
+    pub fn apply(&self, config: &mut CameraConfig) {
+        if let Some(step) = self.pan_step {
+            config.pan.step = step;
+        }
+        if let Some(step) = self.tilt_step {
+            config.tilt.step = step;
+        }
+        if let Some(step) = self.zoom_step {
+            config.zoom.step = step;
+        }
+        if self.zoom_max_step.is_some() {
+            config.zoom.max_step = self.zoom_max_step;
+        }
+        if self.zoom_edge_ramp.is_some() {
+            config.zoom.edge_ramp = self.zoom_edge_ramp;
+        }
+        if let Some(zoom_adjust) = self.zoom_adjust {
+            config.movement.zoom_adjust = zoom_adjust;
+        }
+    }
+
+    /// Puts back settings taken with `capture`, including an unset zoom cap or ramp
+    pub fn restore(&self, config: &mut CameraConfig) {
+        self.apply(config);
+        config.zoom.max_step = self.zoom_max_step;
+        config.zoom.edge_ramp = self.zoom_edge_ramp;
+    }
+}
+
+/// Where to move on launch. Axes left out keep the position read from the device.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StartupPosition {
+    pub pan: Option<i32>,
+    pub tilt: Option<i32>,
+    pub zoom: Option<i32>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Top-level `config.toml`. Missing fields and sections fall back to `CameraConfig::default()`.
+/// Unknown keys are rejected by name, here and in every section, so a typo can't silently
+/// leave a setting at its default; sections added later stay optional for older configs.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
 pub struct CameraConfig {
     pub device: String,
     pub pan: ControlConfig,
     pub tilt: ControlConfig,
     pub zoom: ControlConfig,
+    pub video: VideoConfig,
+    /// Device used for PTZ commands while the video preview is running, for cameras
+    /// whose capture node is grabbed exclusively by the player (e.g. `/dev/v4l-subdev0`)
+    pub preview_control_device: Option<String>,
+    /// Read each control back after setting it and adopt the value the device reports
+    pub verify_writes: bool,
+    /// Read pan, tilt and zoom back after recalling a preset, re-sending once if the camera
+    /// didn't get there, and report whether it did
+    pub verify_presets: bool,
+    /// With `verify_presets`: how many units an axis may be off and still count as reached
+    pub verify_tolerance: i32,
+    /// Ring the terminal bell once when a camera command succeeds and twice when it fails
+    pub audible_feedback: bool,
+    /// Return home after this many seconds without a successful user command; unset disables it
+    pub watchdog_timeout_secs: Option<u64>,
+    /// How long to wait after a move completes before capturing a frame, so it isn't mid-motion
+    pub settle_ms: u64,
+    /// Overwriting a saved preset takes a second Enter within a few seconds
+    pub confirm_preset_overwrite: bool,
+    /// Key overrides from the `[keys]` section
+    pub keys: KeyBindings,
+    /// Display settings from the `[ui]` section
+    pub ui: UiConfig,
+    /// Readout settings from the `[display]` section
+    pub display: DisplayConfig,
+    /// Keys that run a shell command, from the `[hooks]` section
+    pub hooks: Hooks,
+    /// Framing to move to right after startup, from the `[startup_position]` section
+    pub startup_position: Option<StartupPosition>,
+    pub movement: MovementConfig,
+    /// Prometheus endpoint from the `[metrics]` section; needs the `metrics` feature
+    pub metrics: Option<MetricsConfig>,
+    /// Motion profile active at startup, overridden by `--profile`
+    pub motion_profile: Option<String>,
+    /// Named motion profiles from `[profile.<name>]` sections
+    pub profile: BTreeMap<String, MotionProfile>,
+    /// Bookmarks kept in this file, keyed by slot number, from the `[presets]` section.
+    /// When present it is used instead of `presets.toml`.
+    pub presets: Option<BTreeMap<String, Preset>>,
+}
+
+impl Default for CameraConfig {
+    /// `/dev/video0` with the pan/tilt ranges common to UVC PTZ cameras (arc-seconds,
+    /// ±130°/±90°) and a 0–100 zoom, matching the example `config.toml`. All optional
+    /// features are off.
+    fn default() -> Self {
+        CameraConfig {
+            device: "/dev/video0".to_string(),
+            pan: ControlConfig { min: -468000, max: 468000, step: 30000, ..ControlConfig::default() },
+            tilt: ControlConfig { min: -324000, max: 324000, step: 30000, ..ControlConfig::default() },
+            zoom: ControlConfig { min: 0, max: 100, step: 10, ..ControlConfig::default() },
+            video: VideoConfig::default(),
+            preview_control_device: None,
+            verify_writes: false,
+            verify_presets: false,
+            verify_tolerance: 0,
+            audible_feedback: false,
+            watchdog_timeout_secs: None,
+            settle_ms: 300,
+            confirm_preset_overwrite: false,
+            keys: KeyBindings::default(),
+            ui: UiConfig::default(),
+            display: DisplayConfig::default(),
+            hooks: Hooks::default(),
+            startup_position: None,
+            movement: MovementConfig::default(),
+            metrics: None,
+            motion_profile: None,
+            profile: BTreeMap::new(),
+            presets: None,
+        }
+    }
 }
 
+impl CameraConfig {
+    /// Reads and parses the config at `path`, or TOML piped on stdin when `path` is `-`.
+    /// The text is returned too, so the file can be rewritten without losing comments.
+    pub fn load(path: &Path) -> Result<(CameraConfig, String)> {
+        let (text, source, file) = read_config_text(path)?;
+        Ok((Self::parse(&text, &source, file)?, text))
+    }
+
+    /// Parses config text read from `source`; empty text gives all defaults.
+    /// `include` paths are relative to `file`, or to the working directory without one.
+    pub fn parse(text: &str, source: &str, file: Option<&Path>) -> Result<CameraConfig> {
+        let table: toml::Table = toml::from_str(text).with_context(|| format!("Failed to parse {}", source))?;
+        if !table.contains_key("include") && !has_percent_step(&table) {
+            // Straight from the text, so errors point at the line
+            return toml::from_str(text).with_context(|| format!("Failed to parse {}", source));
+        }
+        let what = if table.contains_key("include") { format!("{} with its includes", source) } else { source.to_string() };
+        let mut chain: Vec<PathBuf> = file.and_then(|file| file.canonicalize().ok()).into_iter().collect();
+        let dir = file.and_then(Path::parent).unwrap_or(Path::new(""));
+        let mut merged = with_includes(table, dir, &mut chain).with_context(|| format!("In the includes of {}", source))?;
+        for axis in Axis::ALL {
+            resolve_percent_step(&mut merged, axis.name()).with_context(|| format!("Failed to parse {}", what))?;
+        }
+        merged.try_into().with_context(|| format!("Failed to parse {}", what))
+    }
+
+    /// Like `load`, but recoverable problems become warnings; see `parse_lenient`
+    pub fn load_lenient(path: &Path) -> Result<(CameraConfig, String, Vec<String>)> {
+        let (text, source, file) = read_config_text(path)?;
+        let (config, warnings) = Self::parse_lenient(&text, &source, file);
+        Ok((config, text, warnings))
+    }
+
+    /// Like `parse`, but a top-level setting or section that fails to parse or validate is
+    /// left at its defaults, with a warning saying so, instead of failing the whole config.
+    /// Text that isn't TOML at all gives the built-in defaults.
+    pub fn parse_lenient(text: &str, source: &str, file: Option<&Path>) -> (CameraConfig, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut table: toml::Table = match toml::from_str(text) {
+            Ok(table) => table,
+            Err(e) => {
+                warnings.push(format!("Failed to parse {}: {}; using the built-in defaults", source, e.to_string().trim()));
+                return (CameraConfig::default(), warnings);
+            }
+        };
+        if table.contains_key("include") {
+            let mut chain: Vec<PathBuf> = file.and_then(|file| file.canonicalize().ok()).into_iter().collect();
+            let dir = file.and_then(Path::parent).unwrap_or(Path::new(""));
+            match with_includes(table.clone(), dir, &mut chain) {
+                Ok(merged) => table = merged,
+                Err(e) => {
+                    warnings.push(format!("In the includes of {}: {:#}; ignoring them", source, e));
+                    table.remove("include");
+                }
+            }
+        }
+        for axis in Axis::ALL {
+            if let Err(e) = resolve_percent_step(&mut table, axis.name()) {
+                warnings.push(format!("{}: {:#}; using the defaults for [{}]", source, e, axis.name()));
+                table.remove(axis.name());
+            }
+        }
+        let label = |key: &str, value: &toml::Value| if value.is_table() { format!("[{}]", key) } else { format!("`{}`", key) };
+
+        // Each setting on its own, for type errors and unknown keys
+        for (key, value) in table.clone() {
+            let alone = toml::Table::from_iter([(key.clone(), value.clone())]);
+            if let Err(e) = alone.try_into::<CameraConfig>() {
+                warnings.push(format!("{}: {}; using the defaults for {}", source, e.to_string().trim(), label(&key, &value)));
+                table.remove(&key);
+            }
+        }
+        // Every setting parsed alone above, so this shouldn't fail; if it does, say so
+        let mut config: CameraConfig = match table.clone().try_into() {
+            Ok(config) => config,
+            Err(e) => {
+                warnings.push(format!("{}: {}; using the built-in defaults", source, e.to_string().trim()));
+                return (CameraConfig::default(), warnings);
+            }
+        };
+        // Then together, dropping the setting the validation error goes away (or changes)
+        // without. `motion_profile` and `[hooks]` are checked against `[profile]` and `[keys]`,
+        // so those two are tried last: dropping the setting that refers to them loses less.
+        while let Err(e) = config.validate() {
+            let mut keys: Vec<&String> = table.keys().collect();
+            keys.sort_by_key(|key| ["profile", "keys"].contains(&key.as_str()));
+            let culprit = keys.into_iter().find_map(|key| {
+                let mut without = table.clone();
+                without.remove(key);
+                let config: CameraConfig = without.try_into().ok()?;
+                let fixed = config.validate().map_or_else(|other| other.to_string() != e.to_string(), |()| true);
+                fixed.then(|| (key.clone(), label(key, &table[key]), config))
+            });
+            let Some((key, label, fixed)) = culprit else {
+                warnings.push(format!("{}: {:#}; using the built-in defaults", source, e));
+                return (CameraConfig::default(), warnings);
+            };
+            warnings.push(format!("{}: {:#}; using the defaults for {}", source, e, label));
+            table.remove(&key);
+            config = fixed;
+        }
+        (config, warnings)
+    }
+
+    /// The node video is read from: `[video] capture_device`, or the control `device`
+    pub fn capture_device(&self) -> &str {
+        self.video.capture_device.as_deref().unwrap_or(&self.device)
+    }
+
+    pub fn control(&self, axis: Axis) -> &ControlConfig {
+        match axis {
+            Axis::Pan => &self.pan,
+            Axis::Tilt => &self.tilt,
+            Axis::Zoom => &self.zoom,
+        }
+    }
+
+    /// The control `axis` is driven by: its `control` setting in v4l2-ctl's spelling, or
+    /// the standard UVC name
+    pub fn control_name(&self, axis: Axis) -> String {
+        self.control(axis).control.as_deref().map_or_else(|| axis.control().to_string(), v4l2_control_name)
+    }
+
+    /// The axis driven by the control v4l2-ctl calls `name`
+    pub fn axis_for_control(&self, name: &str) -> Option<Axis> {
+        Axis::ALL.into_iter().find(|axis| self.control_name(*axis) == name)
+    }
+
+    pub fn control_mut(&mut self, axis: Axis) -> &mut ControlConfig {
+        match axis {
+            Axis::Pan => &mut self.pan,
+            Axis::Tilt => &mut self.tilt,
+            Axis::Zoom => &mut self.zoom,
+        }
+    }
+
+    /// Unbinds the default keys that `[hooks]` entries take over, so a hook on e.g. `Enter`
+    /// runs its command rather than the action that key has by default
+    #[cfg(feature = "tui")]
+    pub fn release_hooked_keys(&mut self) {
+        for (key, _) in self.hooks.iter() {
+            self.keys.release_default(key);
+        }
+    }
+
+    /// Checks the settings are usable before a controller is built from them
+    pub fn validate(&self) -> Result<()> {
+        if self.device.trim().is_empty() {
+            bail!("device must not be empty");
+        }
+        #[cfg(feature = "tui")]
+        for (key, _) in self.hooks.iter() {
+            let event = crossterm::event::KeyEvent::new(key.code, key.modifiers);
+            // A key an action has only by default goes to the hook; see `release_hooked_keys`
+            if let Some(action) = self.keys.action_for(&event).filter(|action| self.keys.is_explicit(*action)) {
+                bail!("[hooks] key '{}' is already bound to {:?}", key, action);
+            }
+            if let Some((control, _)) = self.keys.image_step_for(&event) {
+                bail!("[hooks] key '{}' is already bound to image control {}", key, control);
+            }
+        }
+        if let Some(name) = self.motion_profile.as_ref().filter(|name| !self.profile.contains_key(*name)) {
+            bail!("Unknown motion profile '{}' (defined: {})", name, self.profile_names().join(", "));
+        }
+        if self.verify_tolerance < 0 {
+            bail!("verify_tolerance can't be negative, got {}", self.verify_tolerance);
+        }
+        if self.movement.adaptive_min_ms > self.movement.adaptive_max_ms {
+            bail!(
+                "[movement] adaptive_min_ms ({}) must not be more than adaptive_max_ms ({})",
+                self.movement.adaptive_min_ms,
+                self.movement.adaptive_max_ms
+            );
+        }
+        if let Some(slot) = self.presets.iter().flatten().map(|(slot, _)| slot).find(|slot| preset_slot(slot).is_none()) {
+            bail!("[presets] slot '{}' must be a number from 1 up", slot);
+        }
+        for (name, profile) in &self.profile {
+            let mut overlaid = self.clone();
+            profile.apply(&mut overlaid);
+            overlaid.validate_axes().with_context(|| format!("In [profile.{}]", name))?;
+        }
+        self.validate_axes()
+    }
+
+    fn validate_axes(&self) -> Result<()> {
+        self.pan.validate("pan")?;
+        self.tilt.validate("tilt")?;
+        self.zoom.validate("zoom")
+    }
+
+    /// The names of the `[profile.<name>]` sections, in order
+    pub fn profile_names(&self) -> Vec<String> {
+        self.profile.keys().cloned().collect()
+    }
+
+    /// The `[presets]` bookmarks in slot order, or `None` without a `[presets]` section
+    pub fn inline_presets(&self) -> Option<Vec<Preset>> {
+        let mut slots: Vec<(usize, Preset)> = self
+            .presets
+            .as_ref()?
+            .iter()
+            .filter_map(|(slot, preset)| Some((preset_slot(slot)?, preset.clone())))
+            .collect();
+        slots.sort_by_key(|(slot, _)| *slot);
+        Some(slots.into_iter().map(|(_, preset)| preset).collect())
+    }
+}
+
+/// The config text at `path` (stdin for `-`), a name for it in messages, and the file
+/// includes are relative to. An empty file means all defaults, but nothing on stdin is an
+/// error, since it's more likely a generator that failed than a config.
+fn read_config_text(path: &Path) -> Result<(String, String, Option<&Path>)> {
+    if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).context("Failed to read the config from stdin")?;
+        if text.trim().is_empty() {
+            bail!("the config on stdin is empty");
+        }
+        Ok((text, "the config on stdin".to_string(), None))
+    } else {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok((text, path.display().to_string(), Some(path)))
+    }
+}
+
+/// A `[presets]` key as a slot number, counting from 1
+fn preset_slot(key: &str) -> Option<usize> {
+    key.parse().ok().filter(|slot| *slot >= 1)
+}
+
+/// Replaces the `include = ["common.toml", ...]` list in `table` with the files it names,
+/// merged in order, then lays `table` itself on top. Paths are relative to `dir`, the
+/// including file's directory; `chain` holds the files being included, to catch cycles.
+fn with_includes(mut table: toml::Table, dir: &Path, chain: &mut Vec<PathBuf>) -> Result<toml::Table> {
+    let Some(includes) = table.remove("include") else {
+        return Ok(table);
+    };
+    let includes: Vec<String> = includes.try_into().context("include must be a list of file names")?;
+    let mut merged = toml::Table::new();
+    for include in includes {
+        let path = dir.join(&include);
+        let canonical = path.canonicalize().with_context(|| format!("Included file {} not found", path.display()))?;
+        if chain.contains(&canonical) {
+            let names: Vec<String> = chain.iter().chain([&canonical]).map(|file| file.display().to_string()).collect();
+            bail!("Include cycle: {}", names.join(" -> "));
+        }
+        let text = std::fs::read_to_string(&canonical).with_context(|| format!("Failed to read {}", path.display()))?;
+        let included: toml::Table = toml::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))?;
+        chain.push(canonical.clone());
+        let included = with_includes(included, canonical.parent().unwrap_or(dir), chain)?;
+        chain.pop();
+        merge_tables(&mut merged, included);
+    }
+    merge_tables(&mut merged, table);
+    Ok(merged)
+}
+
+/// Whether a file pulled in by the `include` list of the config `text` at `file` sets the
+/// top-level `key`, so that a value for it written to `file` would only be laid on top
+pub fn includes_set(text: &str, file: &Path, key: &str) -> Result<bool> {
+    let mut table: toml::Table = toml::from_str(text).with_context(|| format!("Failed to parse {}", file.display()))?;
+    let Some(includes) = table.remove("include") else {
+        return Ok(false);
+    };
+    let mut chain: Vec<PathBuf> = file.canonicalize().ok().into_iter().collect();
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let included = with_includes(toml::Table::from_iter([("include".to_string(), includes)]), dir, &mut chain)?;
+    Ok(included.contains_key(key))
+}
+
+/// Lays `over` on top of `base`: tables merge key by key, any other value replaces
+fn merge_tables(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(over)) => merge_tables(base, over),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Extra ffplay arguments for one preview latency/smoothness trade-off
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VideoProfile {
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct VideoConfig {
+    /// Profile active at startup; falls back to the first profile by name
+    pub default_profile: Option<String>,
+    /// Named profiles from `[video.profile.<name>]` sections
+    pub profile: BTreeMap<String, VideoProfile>,
+    /// Stop the preview after this many seconds without a key press; unset disables it
+    pub idle_timeout_secs: Option<u64>,
+    /// Restart a preview stopped for idleness on the next key press
+    pub auto_resume: bool,
+    /// Relaunch a preview that was running when the capture device disappeared, once it's back
+    pub auto_relaunch: bool,
+    /// How long to wait for the player to exit after SIGTERM before sending SIGKILL
+    pub cleanup_timeout_ms: u64,
+    /// Node the preview and snapshots read video from, for cameras whose capture node
+    /// isn't the control `device`; unset uses `device`
+    pub capture_device: Option<String>,
+}
+
+impl Default for VideoConfig {
+    /// A single `low_latency` profile with the flags the preview has always used
+    fn default() -> Self {
+        let args = ["-fflags", "nobuffer", "-flags", "low_delay", "-framedrop", "-sync", "ext"];
+        VideoConfig {
+            default_profile: None,
+            profile: BTreeMap::from([(
+                "low_latency".to_string(),
+                VideoProfile { args: args.iter().map(|a| a.to_string()).collect() },
+            )]),
+            idle_timeout_secs: None,
+            auto_resume: false,
+            auto_relaunch: false,
+            cleanup_timeout_ms: 1000,
+            capture_device: None,
+        }
+    }
+}
+
+/// One of the three PTZ axes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Pan,
+    Tilt,
+    Zoom,
+}
+
+impl Axis {
+    pub const ALL: [Axis; 3] = [Axis::Pan, Axis::Tilt, Axis::Zoom];
+
+    /// Name used in config sections and on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Axis::Pan => "pan",
+            Axis::Tilt => "tilt",
+            Axis::Zoom => "zoom",
+        }
+    }
+
+    /// The standard UVC control that drives the axis; see `CameraConfig::control_name`
+    pub fn control(self) -> &'static str {
+        match self {
+            Axis::Pan => "pan_absolute",
+            Axis::Tilt => "tilt_absolute",
+            Axis::Zoom => "zoom_absolute",
+        }
+    }
+
+    pub fn from_control(control: &str) -> Option<Axis> {
+        Axis::ALL.into_iter().find(|axis| axis.control() == control)
+    }
+}
+
+/// What a single-axis move did: the value asked for, where the axis ended up, whether a
+/// command went to the device and whether the request was cut short by the axis limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveOutcome {
+    pub requested: i32,
+    pub achieved: i32,
+    pub command_sent: bool,
+    pub clamped: bool,
+}
+
+impl FromStr for Axis {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match Axis::ALL.into_iter().find(|axis| axis.name() == s) {
+            Some(axis) => Ok(axis),
+            None => bail!("Unknown axis '{}' (expected pan, tilt or zoom)", s),
+        }
+    }
+}
+
+/// How `v4l2-ctl` expects the value of `--set-ctrl`/`--get-ctrl` to be passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CtrlArgStyle {
+    /// `--set-ctrl pan_absolute=0`, accepted by v4l-utils 1.0 and later
+    Separate,
+    /// `--set-ctrl=pan_absolute=0`, required by older releases
+    Joined,
+}
+
+impl CtrlArgStyle {
+    /// Runs `v4l2-ctl --version` once and picks the matching style.
+    /// Falls back to `Separate` if the version can't be determined.
+    pub fn detect(executor: &dyn Executor) -> Self {
+        executor
+            .run(&["--version".to_string()])
+            .map(|output| Self::from_version_output(&output.stdout))
+            .unwrap_or(CtrlArgStyle::Separate)
+    }
+
+    /// Parses output such as "v4l2-ctl 1.22.1"
+    fn from_version_output(output: &str) -> Self {
+        let major = output
+            .split_whitespace()
+            .find_map(|word| word.split('.').next()?.parse::<u32>().ok());
+        match major {
+            Some(0) => CtrlArgStyle::Joined,
+            _ => CtrlArgStyle::Separate,
+        }
+    }
+}
+
+/// A control name as v4l2-ctl spells it. v4l2-ctl has no quoting for `--set-ctrl` and
+/// `--get-ctrl` lists, splitting them on `,` and `=`; instead it names every control by
+/// lowercasing the driver's name and joining the runs of letters and digits with `_`,
+/// so `Absolute Pan` becomes `absolute_pan`. Names already in that form are unchanged.
+pub fn v4l2_control_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut gap = false;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if gap && !out.is_empty() {
+                out.push('_');
+            }
+            gap = false;
+            out.push(c.to_ascii_lowercase());
+        } else {
+            gap = true;
+        }
+    }
+    out
+}
+
+/// Builds the `v4l2-ctl` argument list for one control operation on `device`
+fn v4l2_args(style: CtrlArgStyle, device: &str, flag: &str, value: &str) -> Vec<String> {
+    let mut args = vec!["-d".to_string(), device.to_string()];
+    match style {
+        CtrlArgStyle::Separate => args.extend([flag.to_string(), value.to_string()]),
+        CtrlArgStyle::Joined => args.push(format!("{}={}", flag, value)),
+    }
+    args
+}
+
+/// Fraction of the base pan/tilt step used at full zoom: 10x slower for precise control
+const MIN_ZOOM_FACTOR: f64 = 0.1;
+
+/// Scales a pan/tilt step by the zoom level.
+/// When zoomed in (higher zoom values), movements should be smaller and more precise.
+/// When zoomed out (lower zoom values), movements can be larger.
+/// The factor runs linearly from 1.0 at `zoom_min` to `min_factor` at `zoom_max`, and the
+/// scaled step is truncated toward zero.
+pub fn zoom_adjusted_step(base_step: i32, zoom_current: i32, zoom_min: i32, zoom_max: i32, min_factor: f64) -> i32 {
+    let zoom_range = zoom_max as f64 - zoom_min as f64;
+    let zoom_normalized = (zoom_current as f64 - zoom_min as f64) / zoom_range;
+    let zoom_factor = 1.0 - (zoom_normalized * (1.0 - min_factor));
+
+    (base_step as f64 * zoom_factor) as i32
+}
+
+/// Slowest an `edge_ramp` gets, as a fraction of the step, right at a zoom limit
+const MIN_EDGE_RAMP_FACTOR: f64 = 0.25;
+
+/// Shapes a zoom step with the optional `max_step` cap and `edge_ramp` slowdown.
+/// With neither configured the step is returned unchanged.
+pub fn ramped_zoom_step(step: i32, zoom_current: i32, config: &ControlConfig) -> i32 {
+    let mut step = match config.max_step {
+        Some(cap) => step.min(cap),
+        None => step,
+    };
+    if let Some(ramp) = config.edge_ramp.filter(|ramp| *ramp > 0.0) {
+        let edge_zone = (config.max as f64 - config.min as f64) * ramp;
+        let distance = (zoom_current as f64 - config.min as f64).min(config.max as f64 - zoom_current as f64);
+        if distance < edge_zone {
+            let factor = (distance / edge_zone).max(MIN_EDGE_RAMP_FACTOR);
+            step = ((step as f64 * factor).round() as i32).max(1);
+        }
+    }
+    step
+}
+
+/// Parses `--get-ctrl` output: one `name: value` line per control
+fn parse_ctrl_values(output: &str) -> Result<Vec<(String, i32)>> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| match line.split_once(':') {
+            Some((name, value)) => Ok((name.trim().to_string(), value.trim().parse()?)),
+            None => bail!("Unexpected v4l2-ctl output: {}", line.trim()),
+        })
+        .collect()
+}
+
+/// Number of recent `v4l2-ctl` calls the latency average covers
+const LATENCY_WINDOW: usize = 20;
+
+/// A `v4l2-ctl` call slower than this is flagged as slow
+pub const SLOW_COMMAND_THRESHOLD: Duration = Duration::from_millis(250);
+
+/// How long recent `v4l2-ctl` calls took, to tell a slow device from a slow app
+#[derive(Debug, Clone, Default)]
+pub struct CommandLatency {
+    recent: VecDeque<Duration>,
+}
+
+impl CommandLatency {
+    fn record(&mut self, elapsed: Duration) {
+        if self.recent.len() == LATENCY_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(elapsed);
+    }
+
+    pub fn last(&self) -> Option<Duration> {
+        self.recent.back().copied()
+    }
+
+    /// Mean over the last `LATENCY_WINDOW` calls
+    pub fn average(&self) -> Option<Duration> {
+        let total: Duration = self.recent.iter().sum();
+        Some(total / u32::try_from(self.recent.len()).ok().filter(|n| *n > 0)?)
+    }
+
+    pub fn samples(&self) -> usize {
+        self.recent.len()
+    }
+
+    /// Whether the last call took longer than `SLOW_COMMAND_THRESHOLD`
+    pub fn is_slow(&self) -> bool {
+        self.last().is_some_and(|last| last > SLOW_COMMAND_THRESHOLD)
+    }
+}
+
+/// How many `v4l2-ctl` control calls have been made, and how many of them failed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommandCounts {
+    pub sent: u64,
+    /// Calls that exited unsuccessfully or couldn't be started
+    pub failed: u64,
+}
+
+/// Where the camera is, as published to `CameraController::subscribe` receivers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub pan: i32,
+    pub tilt: i32,
+    pub zoom: i32,
+}
+
+/// Position updates buffered per receiver. A receiver that falls further behind gets
+/// `RecvError::Lagged` once and then continues from the oldest update still buffered, so
+/// a slow consumer skips intermediate positions but never blocks the controller.
+pub const POSITION_CHANNEL_CAPACITY: usize = 64;
+
 pub struct CameraController {
     pub config: CameraConfig, // This was just made public
     pan_current: i32,
@@ -26,10 +1051,78 @@ pub struct CameraController {
     pan_prev: i32,
     tilt_prev: i32,
     zoom_prev: i32,
+    write_note: Option<String>,
+    preview_active: bool,
+    arg_style: CtrlArgStyle,
+    position_tx: broadcast::Sender<Position>,
+    executor: Arc<dyn Executor>,
+    /// Per axis (in `Axis::ALL` order): the step `--list-ctrls` reported, once queried
+    device_steps: [Option<i32>; 3],
+    /// Timings of control reads and writes; a `RefCell` because reads take `&self`
+    latency: RefCell<CommandLatency>,
+    counts: Cell<CommandCounts>,
+}
+
+/// Assembles a `CameraController` without spelling out the nested config structs.
+/// Anything not set keeps its `CameraConfig::default()` value.
+///
+/// ```no_run
+/// # use tiny_ptz::camera::CameraController;
+/// let controller = CameraController::builder()
+///     .device("/dev/video0")
+///     .pan(-468000, 468000, 3600)
+///     .build()?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CameraControllerBuilder {
+    config: CameraConfig,
+}
+
+impl CameraControllerBuilder {
+    pub fn device(mut self, device: impl Into<String>) -> Self {
+        self.config.device = device.into();
+        self
+    }
+
+    pub fn pan(mut self, min: i32, max: i32, step: i32) -> Self {
+        self.config.pan = ControlConfig { min, max, step, ..self.config.pan };
+        self
+    }
+
+    pub fn tilt(mut self, min: i32, max: i32, step: i32) -> Self {
+        self.config.tilt = ControlConfig { min, max, step, ..self.config.tilt };
+        self
+    }
+
+    pub fn zoom(mut self, min: i32, max: i32, step: i32) -> Self {
+        self.config.zoom = ControlConfig { min, max, step, ..self.config.zoom };
+        self
+    }
+
+    pub fn verify_writes(mut self, verify: bool) -> Self {
+        self.config.verify_writes = verify;
+        self
+    }
+
+    /// Validates the assembled config and constructs the controller
+    pub fn build(self) -> Result<CameraController> {
+        self.config.validate()?;
+        Ok(CameraController::new(self.config))
+    }
 }
 
 impl CameraController {
+    pub fn builder() -> CameraControllerBuilder {
+        CameraControllerBuilder::default()
+    }
+
     pub fn new(config: CameraConfig) -> Self {
+        Self::with_executor(config, Arc::new(V4l2Ctl))
+    }
+
+    /// A controller that runs its `v4l2-ctl` commands through `executor`
+    pub fn with_executor(config: CameraConfig, executor: Arc<dyn Executor>) -> Self {
         CameraController {
             config,
             pan_current: 0,
@@ -38,21 +1131,39 @@ impl CameraController {
             pan_prev: 0,
             tilt_prev: 0,
             zoom_prev: 50,
+            write_note: None,
+            device_steps: [None; 3],
+            latency: RefCell::new(CommandLatency::default()),
+            counts: Cell::new(CommandCounts::default()),
+            preview_active: false,
+            arg_style: CtrlArgStyle::detect(executor.as_ref()),
+            position_tx: broadcast::channel(POSITION_CHANNEL_CAPACITY).0,
+            executor,
         }
     }
 
-    /// Calculate zoom-adjusted step value for pan/tilt movements
-    /// When zoomed in (higher zoom values), movements should be smaller and more precise
-    /// When zoomed out (lower zoom values), movements can be larger
+    /// Receives a `Position` every time a command is applied or the position is re-read
+    /// from the device. See `POSITION_CHANNEL_CAPACITY` for what happens when a receiver lags.
+    pub fn subscribe(&self) -> broadcast::Receiver<Position> {
+        self.position_tx.subscribe()
+    }
+
+    pub fn position(&self) -> Position {
+        Position { pan: self.pan_current, tilt: self.tilt_current, zoom: self.zoom_current }
+    }
+
+    fn publish_position(&self) {
+        // Sending only fails when nobody is subscribed, which is fine
+        let _ = self.position_tx.send(self.position());
+    }
+
+    /// Calculate zoom-adjusted step value for pan/tilt movements. Worked out afresh from
+    /// `config.zoom` on every call, so new zoom limits apply from the next key press.
     fn get_zoom_adjusted_step(&self, base_step: i32) -> i32 {
-        let zoom_range = self.config.zoom.max - self.config.zoom.min;
-        let zoom_normalized = (self.zoom_current - self.config.zoom.min) as f64 / zoom_range as f64;
-        
-        // Calculate zoom factor: 1.0 at min zoom (faster), 0.1 at max zoom (slower/precise)
-        // This means movements are 10x slower when fully zoomed in for precise control
-        let zoom_factor = 1.0 - (zoom_normalized * 0.9);
-        
-        (base_step as f64 * zoom_factor) as i32
+        if !self.config.movement.zoom_adjust {
+            return base_step;
+        }
+        zoom_adjusted_step(base_step, self.zoom_current, self.config.zoom.min, self.config.zoom.max, MIN_ZOOM_FACTOR)
     }
 
     /// Get the current zoom-adjusted step values for display purposes
@@ -64,64 +1175,933 @@ impl CameraController {
         self.get_zoom_adjusted_step(self.config.tilt.step)
     }
 
+    /// Tells the controller whether the video preview currently holds the capture device
+    pub fn set_preview_active(&mut self, active: bool) {
+        self.preview_active = active;
+    }
+
+    /// The node PTZ commands go to: the preview fallback device while the preview runs, if configured
+    fn control_device(&self) -> &str {
+        match (&self.config.preview_control_device, self.preview_active) {
+            (Some(device), true) => device,
+            _ => &self.config.device,
+        }
+    }
+
+    /// The executor commands run through, for running hooks off the UI thread
+    pub fn executor(&self) -> Arc<dyn Executor> {
+        Arc::clone(&self.executor)
+    }
+
+    /// Runs one `v4l2-ctl` control operation on the current control device
+    fn run_ctl(&self, flag: &str, value: &str) -> Result<CtlOutput> {
+        let started = Instant::now();
+        let output = self.executor.run(&v4l2_args(self.arg_style, self.control_device(), flag, value));
+        self.latency.borrow_mut().record(started.elapsed());
+        let mut counts = self.counts.get();
+        counts.sent += 1;
+        if !output.as_ref().is_ok_and(|output| output.success) {
+            counts.failed += 1;
+        }
+        self.counts.set(counts);
+        Ok(output?)
+    }
+
+    /// Totals of the control reads and writes made so far
+    pub fn command_counts(&self) -> CommandCounts {
+        self.counts.get()
+    }
+
+    /// Timings of the recent control reads and writes
+    pub fn latency(&self) -> CommandLatency {
+        self.latency.borrow().clone()
+    }
+
     /// Sends a v4l2 command if the value has changed.
     /// Takes `&self` (immutable borrow) and `current_prev_value` by value.
-    /// Returns Ok(true) if a command was sent successfully, Ok(false) if no change, or Err on failure.
-    fn send_v4l2_command(&self, control_name: &str, value: i32, current_prev_value: i32) -> Result<bool> {
+    /// Returns Ok(Some(achieved)) if a command was sent successfully, Ok(None) if no change, or Err on failure.
+    /// `achieved` is the read-back value when `verify_writes` is on, otherwise the requested value.
+    fn send_v4l2_command(&self, control_name: &str, value: i32, current_prev_value: i32) -> Result<Option<i32>> {
         if current_prev_value == value {
-            return Ok(false); // No change, so don't send a command
+            return Ok(None); // No change, so don't send a command
         }
 
-        let output = Command::new("v4l2-ctl")
-            .arg("-d")
-            .arg(&self.config.device)
-            .arg("--set-ctrl")
-            .arg(format!("{}={}", control_name, value))
-            .output()?;
+        let output = self.run_ctl("--set-ctrl", &format!("{}={}", v4l2_control_name(control_name), value))?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            bail!("Error setting {} to {}: {}", control_name, value, error_msg);
+        if !output.success {
+            bail!("Error setting {} to {}: {}", control_name, value, output.stderr);
+        }
+
+        if self.config.verify_writes {
+            Ok(Some(self.read_control(control_name)?))
         } else {
-            Ok(true) // Command was successfully sent
+            Ok(Some(value)) // Command was successfully sent
         }
     }
 
-    // These methods take &mut self to modify current and prev values
-    pub fn set_pan(&mut self, delta: i32) -> Result<()> {
-        // Use zoom-adjusted step for pan movements
-        let adjusted_step = self.get_zoom_adjusted_step(self.config.pan.step);
-        let actual_delta = if delta > 0 { adjusted_step } else { -adjusted_step };
-        
-        self.pan_current = (self.pan_current + actual_delta).clamp(self.config.pan.min, self.config.pan.max);
-        // Call send_v4l2_command (which takes &self) and then update self.pan_prev
-        if self.send_v4l2_command("pan_absolute", self.pan_current, self.pan_prev)? {
-            self.pan_prev = self.pan_current; // Update only if command was actually sent
+    /// Reads the device's current value of a single control via `--get-ctrl`
+    fn read_control(&self, control_name: &str) -> Result<i32> {
+        let wanted = v4l2_control_name(control_name);
+        match self.read_values(&[control_name])?.into_iter().find(|(name, _)| *name == wanted) {
+            Some((_, value)) => Ok(value),
+            None => bail!("v4l2-ctl did not report {}", control_name),
         }
+    }
+
+    /// Reads several controls with a single `v4l2-ctl --get-ctrl a,b,c` invocation. The
+    /// readings come back under v4l2-ctl's names (see `v4l2_control_name`).
+    pub fn read_values(&self, names: &[&str]) -> Result<Vec<(String, i32)>> {
+        let list: Vec<String> = names.iter().map(|name| v4l2_control_name(name)).collect();
+        let output = self.run_ctl("--get-ctrl", &list.join(","))?;
+
+        if !output.success {
+            bail!("Error reading {}: {}", names.join(", "), output.stderr);
+        }
+
+        parse_ctrl_values(&output.stdout)
+    }
+
+    /// Adopts the device's actual pan/tilt/zoom so the first keypress moves from where the camera really is
+    pub fn sync_from_device(&mut self) -> Result<()> {
+        let controls: Vec<String> = self.supported_axes().into_iter().map(|axis| self.config.control_name(axis)).collect();
+        if controls.is_empty() {
+            return Ok(());
+        }
+        let controls: Vec<&str> = controls.iter().map(String::as_str).collect();
+        for (name, value) in self.read_values(&controls)? {
+            self.adopt_value(&name, value);
+        }
+        self.publish_position();
         Ok(())
     }
 
-    pub fn set_tilt(&mut self, delta: i32) -> Result<()> {
-        // Use zoom-adjusted step for tilt movements
-        let adjusted_step = self.get_zoom_adjusted_step(self.config.tilt.step);
-        let actual_delta = if delta > 0 { adjusted_step } else { -adjusted_step };
-        
-        self.tilt_current = (self.tilt_current + actual_delta).clamp(self.config.tilt.min, self.config.tilt.max);
-        if self.send_v4l2_command("tilt_absolute", self.tilt_current, self.tilt_prev)? {
-            self.tilt_prev = self.tilt_current;
+    pub fn supported_axes(&self) -> Vec<Axis> {
+        Axis::ALL.into_iter().filter(|axis| self.config.control(*axis).supported).collect()
+    }
+
+    /// Queries `--list-ctrls` and remembers each axis's device step for `nudge_device_step`
+    fn query_device_ranges(&mut self) -> Result<Vec<crate::detect::ControlRange>> {
+        let ranges = crate::detect::query_ranges(self.executor.as_ref(), &self.config.device)?;
+        for axis in Axis::ALL {
+            // Record 1 for controls the device doesn't list so they aren't queried again
+            let name = self.config.control_name(axis);
+            let step = ranges.iter().find(|range| range.name == name).map_or(1, |range| range.step);
+            self.device_steps[axis as usize] = Some(step.max(1));
+        }
+        Ok(ranges)
+    }
+
+    /// Marks axes whose control the device doesn't list as unsupported, returning them.
+    /// Leaves the config alone if the device reports no integer controls at all.
+    pub fn detect_unsupported_axes(&mut self) -> Result<Vec<Axis>> {
+        let ranges = self.query_device_ranges()?;
+        if ranges.is_empty() {
+            return Ok(Vec::new());
+        }
+        let missing: Vec<Axis> = self
+            .supported_axes()
+            .into_iter()
+            .filter(|axis| !ranges.iter().any(|range| range.name == self.config.control_name(*axis)))
+            .collect();
+        for axis in &missing {
+            self.config.control_mut(*axis).supported = false;
+        }
+        Ok(missing)
+    }
+
+    /// Records `value` as both the current and last-sent value of a PTZ control
+    fn adopt_value(&mut self, control_name: &str, value: i32) {
+        match self.config.axis_for_control(&v4l2_control_name(control_name)) {
+            Some(Axis::Pan) => (self.pan_current, self.pan_prev) = (value, value),
+            Some(Axis::Tilt) => (self.tilt_current, self.tilt_prev) = (value, value),
+            Some(Axis::Zoom) => (self.zoom_current, self.zoom_prev) = (value, value),
+            None => {}
+        }
+    }
+
+    /// Records a note for the UI when the device settled on a different value than requested
+    fn note_achieved(&mut self, control_name: &str, requested: i32, achieved: i32) {
+        if requested != achieved {
+            self.write_note = Some(format!("{}: requested {}, camera reports {}", control_name, requested, achieved));
+        }
+    }
+
+    /// Takes the pending requested-vs-achieved note, if the last write produced one
+    pub fn take_write_note(&mut self) -> Option<String> {
+        self.write_note.take()
+    }
+
+    /// Where one step of `delta` would move `axis`, without sending anything. Pan and tilt
+    /// move by the zoom-adjusted step in the direction of `delta`; zoom moves by `|delta|`,
+    /// shaped by `max_step`/`edge_ramp`.
+    pub fn stepped(&self, axis: Axis, delta: i32) -> i32 {
+        let config = self.config.control(axis);
+        self.step_target(axis, delta).clamp(config.min, config.max)
+    }
+
+    /// `stepped` before clamping to the axis limits
+    fn step_target(&self, axis: Axis, delta: i32) -> i32 {
+        let step = match axis {
+            Axis::Pan | Axis::Tilt => self.get_zoom_adjusted_step(self.config.control(axis).step),
+            Axis::Zoom => ramped_zoom_step(delta.saturating_abs(), self.zoom_current, &self.config.zoom),
+        };
+        let actual_delta = if delta > 0 { step } else { -step };
+        // Saturate rather than wrap for ranges near the i32 limits
+        self.position_of(axis).saturating_add(actual_delta)
+    }
+
+    /// Whether moving `axis` to `value` is a change too small to send under its `deadband`
+    pub fn within_deadband(&self, axis: Axis, value: i32) -> bool {
+        (value as i64 - self.position_of(axis) as i64).abs() < self.config.control(axis).deadband as i64
+    }
+
+    // These methods take &mut self to modify current and prev values
+    pub fn set_pan(&mut self, delta: i32) -> Result<MoveOutcome> {
+        self.move_axis(Axis::Pan, self.step_target(Axis::Pan, delta), true)
+    }
+
+    pub fn set_tilt(&mut self, delta: i32) -> Result<MoveOutcome> {
+        self.move_axis(Axis::Tilt, self.step_target(Axis::Tilt, delta), true)
+    }
+
+    pub fn set_zoom(&mut self, delta: i32) -> Result<MoveOutcome> {
+        self.move_axis(Axis::Zoom, self.step_target(Axis::Zoom, delta), true)
+    }
+
+    /// Moves `axis` to `requested`, clamped to its limits, and sends it unless nothing
+    /// changed or, with `deadband`, the change is too small to send
+    fn move_axis(&mut self, axis: Axis, requested: i32, deadband: bool) -> Result<MoveOutcome> {
+        let config = self.config.control(axis);
+        let value = requested.clamp(config.min, config.max);
+        let clamped = value != requested;
+        let command_sent = if deadband && self.within_deadband(axis, value) {
+            false
+        } else {
+            self.stage(axis, value);
+            match axis {
+                Axis::Pan => self.apply_pan()?,
+                Axis::Tilt => self.apply_tilt()?,
+                Axis::Zoom => self.apply_zoom()?,
+            }
+        };
+        Ok(MoveOutcome { requested, achieved: self.position_of(axis), command_sent, clamped })
+    }
+
+    /// Moves `axis` by exactly one step as the device reports it (`direction` gives the sign),
+    /// ignoring the configured step and zoom adjustment. For calibrating positions.
+    pub fn nudge_device_step(&mut self, axis: Axis, direction: i32) -> Result<MoveOutcome> {
+        let step = match self.device_steps[axis as usize] {
+            Some(step) => step,
+            None => {
+                self.query_device_ranges()?;
+                self.device_steps[axis as usize].unwrap_or(1)
+            }
+        };
+        let delta = step.saturating_mul(direction.signum());
+        self.set_axis(axis, self.position_of(axis).saturating_add(delta))
+    }
+
+    /// Moves `axis` to `value`, clamped to its limits, without sending it. `flush` sends
+    /// every staged axis in one command.
+    pub fn stage(&mut self, axis: Axis, value: i32) {
+        let config = self.config.control(axis);
+        let value = value.clamp(config.min, config.max);
+        match axis {
+            Axis::Pan => self.pan_current = value,
+            Axis::Tilt => self.tilt_current = value,
+            Axis::Zoom => self.zoom_current = value,
         }
+    }
+
+    /// How far `axis` has been staged from the value last sent to the device
+    pub fn staged_distance(&self, axis: Axis) -> i64 {
+        let sent = match axis {
+            Axis::Pan => self.pan_prev,
+            Axis::Tilt => self.tilt_prev,
+            Axis::Zoom => self.zoom_prev,
+        };
+        (self.position_of(axis) as i64 - sent as i64).abs()
+    }
+
+    /// Sends the axes changed by `stage` in a single batched command
+    pub fn flush(&mut self) -> Result<()> {
+        self.apply_batch()
+    }
+
+    /// Drops the moves `stage` has held back, returning every axis to the value last sent
+    pub fn discard_staged(&mut self) {
+        (self.pan_current, self.tilt_current, self.zoom_current) = (self.pan_prev, self.tilt_prev, self.zoom_prev);
+    }
+
+    /// Moves `axis` straight to its configured `min` (negative `direction`) or `max`
+    /// in a single command
+    pub fn jump_to_limit(&mut self, axis: Axis, direction: i32) -> Result<MoveOutcome> {
+        let config = self.config.control(axis);
+        let limit = if direction < 0 { config.min } else { config.max };
+        self.set_axis(axis, limit)
+    }
+
+    /// Sets one axis to `value`, clamped to its limits, and sends it
+    pub fn set_axis(&mut self, axis: Axis, value: i32) -> Result<MoveOutcome> {
+        self.move_axis(axis, value, false)
+    }
+
+    /// Moves an image control such as `brightness` by `delta` from the value the device
+    /// reports, returning the new value. The device keeps it within its own range; with
+    /// `verify_writes` the returned value is read back and reflects that.
+    pub fn set_image_control(&mut self, name: &str, delta: i32) -> Result<i32> {
+        let value = self.read_control(name)?.saturating_add(delta);
+        self.set_controls(&[(name.to_string(), value)])?;
+        if self.config.verify_writes {
+            self.read_control(name)
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Sets arbitrary controls (brightness, exposure, ...) in one `--set-ctrl` call.
+    /// PTZ controls in the list are adopted as the new position.
+    pub fn set_controls(&mut self, controls: &[(String, i32)]) -> Result<()> {
+        if controls.is_empty() {
+            return Ok(());
+        }
+        let assignments = controls
+            .iter()
+            .map(|(name, value)| format!("{}={}", v4l2_control_name(name), value))
+            .collect::<Vec<_>>()
+            .join(",");
+        let output = self.run_ctl("--set-ctrl", &assignments)?;
+        if !output.success {
+            bail!("Error setting {}: {}", assignments, output.stderr.trim());
+        }
+        for (name, value) in controls {
+            self.adopt_value(name, *value);
+        }
+        self.publish_position();
         Ok(())
     }
 
-    pub fn set_zoom(&mut self, delta: i32) -> Result<()> {
-        self.zoom_current = (self.zoom_current + delta).clamp(self.config.zoom.min, self.config.zoom.max);
-        if self.send_v4l2_command("zoom_absolute", self.zoom_current, self.zoom_prev)? {
-            self.zoom_prev = self.zoom_current;
+    /// Moves every axis to an absolute position, clamped to the configured limits
+    /// Unsupported axes stay where they are.
+    pub fn move_to(&mut self, pan: i32, tilt: i32, zoom: i32) -> Result<()> {
+        if self.config.pan.supported {
+            self.pan_current = pan.clamp(self.config.pan.min, self.config.pan.max);
+        }
+        if self.config.tilt.supported {
+            self.tilt_current = tilt.clamp(self.config.tilt.min, self.config.tilt.max);
+        }
+        if self.config.zoom.supported {
+            self.zoom_current = zoom.clamp(self.config.zoom.min, self.config.zoom.max);
+        }
+        self.apply_batch()
+    }
+
+    /// Reads every supported axis back and compares it with `target` (clamped to the
+    /// limits), re-sending the move once if any axis is more than `tolerance` off. Returns
+    /// the axes still off afterwards, with how far; the readings become the current values.
+    pub fn verify_position(&mut self, target: [i32; 3], tolerance: i32) -> Result<Vec<(Axis, i32)>> {
+        let off = self.position_error(target, tolerance)?;
+        if off.is_empty() {
+            return Ok(off);
         }
+        self.move_to(target[0], target[1], target[2])?;
+        self.position_error(target, tolerance)
+    }
+
+    /// The supported axes whose device reading is more than `tolerance` from `target`
+    fn position_error(&mut self, target: [i32; 3], tolerance: i32) -> Result<Vec<(Axis, i32)>> {
+        let axes = self.supported_axes();
+        let controls: Vec<String> = axes.iter().map(|axis| self.config.control_name(*axis)).collect();
+        if controls.is_empty() {
+            return Ok(Vec::new());
+        }
+        let readings = self.read_values(&controls.iter().map(String::as_str).collect::<Vec<_>>())?;
+        let mut off = Vec::new();
+        for (axis, control_name) in axes.into_iter().zip(&controls) {
+            let Some(&(_, value)) = readings.iter().find(|(name, _)| name == control_name) else {
+                bail!("v4l2-ctl did not report {}", control_name);
+            };
+            self.adopt_value(control_name, value);
+            let control = self.config.control(axis);
+            let error = value - target[axis as usize].clamp(control.min, control.max);
+            if error.abs() > tolerance {
+                off.push((axis, error));
+            }
+        }
+        self.publish_position();
+        Ok(off)
+    }
+
+    /// Returns every axis with a configured `default` to it in one batched command.
+    /// Returns how many controls were reset.
+    pub fn reset_to_defaults(&mut self) -> Result<usize> {
+        let mut count = 0;
+        if let Some(pan) = self.config.pan.default.filter(|_| self.config.pan.supported) {
+            self.pan_current = pan.clamp(self.config.pan.min, self.config.pan.max);
+            count += 1;
+        }
+        if let Some(tilt) = self.config.tilt.default.filter(|_| self.config.tilt.supported) {
+            self.tilt_current = tilt.clamp(self.config.tilt.min, self.config.tilt.max);
+            count += 1;
+        }
+        if let Some(zoom) = self.config.zoom.default.filter(|_| self.config.zoom.supported) {
+            self.zoom_current = zoom.clamp(self.config.zoom.min, self.config.zoom.max);
+            count += 1;
+        }
+        self.apply_batch()?;
+        Ok(count)
+    }
+
+    /// Moves to the configured `startup_position`, if any, in one batched command
+    pub fn apply_startup_position(&mut self) -> Result<bool> {
+        let Some(startup) = self.config.startup_position else {
+            return Ok(false);
+        };
+        self.move_to(
+            startup.pan.unwrap_or(self.pan_current),
+            startup.tilt.unwrap_or(self.tilt_current),
+            startup.zoom.unwrap_or(self.zoom_current),
+        )?;
+        Ok(true)
+    }
+
+    /// Moves to the home position: each axis's `default`, or centred pan/tilt and widest zoom
+    pub fn reset_to_home(&mut self) -> Result<()> {
+        let pan = self.config.pan.default.unwrap_or(0);
+        let tilt = self.config.tilt.default.unwrap_or(0);
+        let zoom = self.config.zoom.default.unwrap_or(self.config.zoom.min);
+        self.move_to(pan, tilt, zoom)
+    }
+
+    /// v4l2-ctl may apply some controls of a batch before failing on one. Re-reads them so
+    /// `*_prev` only advances for controls the device actually took, leaving the rest to be
+    /// retried, and builds an error listing the controls that didn't make it.
+    fn recover_partial_batch(&mut self, changed: &[(String, i32)], error_msg: &str) -> anyhow::Error {
+        let names: Vec<&str> = changed.iter().map(|(name, _)| name.as_str()).collect();
+        let Ok(actual) = self.read_values(&names) else {
+            return anyhow::anyhow!("Error setting {}: {}", names.join(", "), error_msg);
+        };
+        let mut failed = Vec::new();
+        for (name, requested) in changed {
+            match actual.iter().find(|(n, _)| n == name) {
+                Some((_, value)) if value == requested => {
+                    let prev = match self.config.axis_for_control(name) {
+                        Some(Axis::Pan) => &mut self.pan_prev,
+                        Some(Axis::Tilt) => &mut self.tilt_prev,
+                        Some(Axis::Zoom) => &mut self.zoom_prev,
+                        None => continue,
+                    };
+                    *prev = *value;
+                }
+                _ => failed.push(format!("{}={}", name, requested)),
+            }
+        }
+        if failed.is_empty() {
+            // Everything landed despite the non-zero exit; report it rather than hide it
+            anyhow::anyhow!("v4l2-ctl reported an error but all controls were applied: {}", error_msg)
+        } else {
+            anyhow::anyhow!("Failed to set {} ({} of {} applied): {}", failed.join(", "), changed.len() - failed.len(), changed.len(), error_msg)
+        }
+    }
+
+    /// Sends every axis whose `*_current` differs from `*_prev` in a single
+    /// `--set-ctrl a=1,b=2` call, then updates `*_prev` like the single-axis path
+    fn apply_batch(&mut self) -> Result<()> {
+        let changed: Vec<(String, i32)> = [
+            (Axis::Pan, self.pan_current, self.pan_prev),
+            (Axis::Tilt, self.tilt_current, self.tilt_prev),
+            (Axis::Zoom, self.zoom_current, self.zoom_prev),
+        ]
+        .into_iter()
+        .filter(|(_, current, prev)| current != prev)
+        .map(|(axis, current, _)| (self.config.control_name(axis), current))
+        .collect();
+        if changed.is_empty() {
+            return Ok(()); // No change, so don't send a command
+        }
+
+        let assignments = changed
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(",");
+        let output = self.run_ctl("--set-ctrl", &assignments)?;
+
+        if !output.success {
+            return Err(self.recover_partial_batch(&changed, output.stderr.trim()));
+        }
+
+        let achieved = if self.config.verify_writes {
+            let names: Vec<&str> = changed.iter().map(|(name, _)| name.as_str()).collect();
+            self.read_values(&names)?
+        } else {
+            changed.iter().map(|(name, value)| (name.to_string(), *value)).collect()
+        };
+        for (name, value) in achieved {
+            if let Some((_, requested)) = changed.iter().find(|(n, _)| *n == name) {
+                self.note_achieved(&name, *requested, value);
+            }
+            self.adopt_value(&name, value);
+        }
+        self.publish_position();
         Ok(())
     }
 
+    // Send `*_current` to the device and update `*_prev` only if a command was actually sent,
+    // returning whether it was
+    fn apply_pan(&mut self) -> Result<bool> {
+        let control_name = self.config.control_name(Axis::Pan);
+        if let Some(achieved) = self.send_v4l2_command(&control_name, self.pan_current, self.pan_prev)? {
+            self.note_achieved(&control_name, self.pan_current, achieved);
+            self.pan_current = achieved;
+            self.pan_prev = achieved;
+            self.publish_position();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn apply_tilt(&mut self) -> Result<bool> {
+        let control_name = self.config.control_name(Axis::Tilt);
+        if let Some(achieved) = self.send_v4l2_command(&control_name, self.tilt_current, self.tilt_prev)? {
+            self.note_achieved(&control_name, self.tilt_current, achieved);
+            self.tilt_current = achieved;
+            self.tilt_prev = achieved;
+            self.publish_position();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn apply_zoom(&mut self) -> Result<bool> {
+        let control_name = self.config.control_name(Axis::Zoom);
+        if let Some(achieved) = self.send_v4l2_command(&control_name, self.zoom_current, self.zoom_prev)? {
+            self.note_achieved(&control_name, self.zoom_current, achieved);
+            self.zoom_current = achieved;
+            self.zoom_prev = achieved;
+            self.publish_position();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    pub fn position_of(&self, axis: Axis) -> i32 {
+        match axis {
+            Axis::Pan => self.pan_current,
+            Axis::Tilt => self.tilt_current,
+            Axis::Zoom => self.zoom_current,
+        }
+    }
+
     pub fn get_pan(&self) -> i32 { self.pan_current }
     pub fn get_tilt(&self) -> i32 { self.tilt_current }
     pub fn get_zoom(&self) -> i32 { self.zoom_current }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake::FakeCamera;
+
+    #[test]
+    fn separate_style_passes_value_as_its_own_argument() {
+        assert_eq!(
+            v4l2_args(CtrlArgStyle::Separate, "/dev/video0", "--set-ctrl", "pan_absolute=3600"),
+            ["-d", "/dev/video0", "--set-ctrl", "pan_absolute=3600"]
+        );
+    }
+
+    #[test]
+    fn joined_style_attaches_value_to_the_flag() {
+        assert_eq!(
+            v4l2_args(CtrlArgStyle::Joined, "/dev/video0", "--set-ctrl", "pan_absolute=3600"),
+            ["-d", "/dev/video0", "--set-ctrl=pan_absolute=3600"]
+        );
+    }
+
+    #[test]
+    fn parses_multi_control_output() {
+        let values = parse_ctrl_values("pan_absolute: 3600\ntilt_absolute: -7200\nzoom_absolute: 50\n").unwrap();
+        assert_eq!(
+            values,
+            [
+                ("pan_absolute".to_string(), 3600),
+                ("tilt_absolute".to_string(), -7200),
+                ("zoom_absolute".to_string(), 50),
+            ]
+        );
+        assert!(parse_ctrl_values("garbage").is_err());
+    }
+
+    #[test]
+    fn zoom_adjusted_step_scales_between_factors() {
+        // (base, zoom, min, max, min_factor, expected)
+        let cases = [
+            (30000, 0, 0, 100, 0.1, 30000),   // fully zoomed out: full step
+            (30000, 100, 0, 100, 0.1, 2999),  // fully zoomed in: min_factor, truncated
+            (30000, 50, 0, 100, 0.1, 16500),  // halfway: 1.0 - 0.5 * 0.9
+            (30000, 50, 0, 100, 1.0, 30000),  // min_factor 1.0 disables the adjustment
+            (1000, 150, 100, 200, 0.5, 750),  // offset zoom range
+        ];
+        for (base, zoom, min, max, factor, expected) in cases {
+            assert_eq!(zoom_adjusted_step(base, zoom, min, max, factor), expected, "zoom {} in {}..={}", zoom, min, max);
+        }
+    }
+
+    #[test]
+    fn adjusted_step_follows_zoom_limits_changed_at_runtime() {
+        let camera = FakeCamera::new().with_control("zoom_absolute", 0, 200, 50);
+        let mut config = CameraConfig::default();
+        config.pan.step = 30000;
+        let mut controller = CameraController::with_executor(config, Arc::new(camera));
+        controller.sync_from_device().unwrap();
+        assert_eq!(controller.get_zoom_adjusted_pan_step(), 16500);
+
+        // Swapping in a config with new zoom bounds, as a reload would, takes effect at once
+        let mut reloaded = controller.config.clone();
+        reloaded.zoom.max = 200;
+        controller.config = reloaded;
+        assert_eq!(controller.get_zoom_adjusted_pan_step(), 23250);
+        controller.config.zoom.min = 50;
+        assert_eq!(controller.get_zoom_adjusted_pan_step(), 30000);
+    }
+
+    #[test]
+    fn zoom_ramp_is_off_unless_configured() {
+        let zoom = ControlConfig { min: 0, max: 100, step: 10, ..ControlConfig::default() };
+        assert_eq!(ramped_zoom_step(10, 0, &zoom), 10);
+
+        let capped = ControlConfig { max_step: Some(4), ..zoom.clone() };
+        assert_eq!(ramped_zoom_step(10, 50, &capped), 4);
+
+        let ramped = ControlConfig { edge_ramp: Some(0.2), ..zoom };
+        assert_eq!(ramped_zoom_step(10, 50, &ramped), 10); // outside the edge zones
+        assert_eq!(ramped_zoom_step(10, 90, &ramped), 5);  // halfway into the top zone
+        assert_eq!(ramped_zoom_step(10, 100, &ramped), 3); // floor at the limit
+    }
+
+    #[test]
+    fn builder_fills_defaults_and_validates() {
+        let controller = CameraController::builder()
+            .device("/dev/video2")
+            .pan(-1000, 1000, 10)
+            .build()
+            .unwrap();
+        assert_eq!(controller.config.device, "/dev/video2");
+        assert_eq!((controller.config.pan.min, controller.config.pan.max), (-1000, 1000));
+        assert_eq!(controller.config.zoom.max, CameraConfig::default().zoom.max);
+
+        let error = CameraController::builder().tilt(10, -10, 1).build().err().unwrap();
+        assert!(error.to_string().contains("[tilt] min"));
+        assert!(CameraController::builder().zoom(0, 100, 0).build().is_err());
+    }
+
+    #[test]
+    fn huge_steps_saturate_at_the_limits_instead_of_wrapping() {
+        let camera = FakeCamera::new()
+            .with_control("pan_absolute", i32::MIN, i32::MAX, i32::MAX - 5)
+            .with_control("zoom_absolute", i32::MIN, i32::MAX, i32::MIN + 5);
+        let mut config = CameraConfig::default();
+        config.pan = ControlConfig { min: -10, max: i32::MAX, step: i32::MAX, ..config.pan };
+        config.zoom = ControlConfig { min: i32::MIN, max: 100, step: 10, ..config.zoom };
+        let mut controller = CameraController::with_executor(config, Arc::new(camera.clone()));
+        controller.sync_from_device().unwrap();
+
+        controller.set_pan(1).unwrap();
+        assert_eq!(controller.get_pan(), i32::MAX);
+        controller.set_pan(-1).unwrap();
+        controller.set_pan(-1).unwrap();
+        assert_eq!(controller.get_pan(), -10);
+        controller.set_zoom(i32::MIN).unwrap();
+        assert_eq!(controller.get_zoom(), i32::MIN);
+        assert_eq!(camera.value("zoom_absolute"), Some(i32::MIN));
+    }
+
+    #[test]
+    fn smooth_moves_bring_every_axis_in_together() {
+        let start = Instant::now();
+        let glide = SmoothMove::new([0, 0, 10], [400000, -40000, 10], start, Duration::from_millis(1000));
+        assert_eq!(glide.position_at(start), ([0, 0, 10], false));
+        assert_eq!(glide.position_at(start + Duration::from_millis(250)), ([100000, -10000, 10], false));
+        assert_eq!(glide.position_at(start + Duration::from_millis(1000)), ([400000, -40000, 10], true));
+        let instant = SmoothMove::new([0; 3], [5, 5, 5], start, Duration::ZERO);
+        assert_eq!(instant.position_at(start), ([5, 5, 5], true));
+    }
+
+    #[test]
+    fn percent_step_moves_a_share_of_the_range() {
+        let mut zoom = ControlConfig { min: 100, max: 500, step: 1, ..ControlConfig::default() };
+        assert_eq!(zoom.key_step(), 1);
+        zoom.percent_step = Some(5.0);
+        assert_eq!(zoom.key_step(), 20);
+        zoom.percent_step = Some(0.01);
+        assert_eq!(zoom.key_step(), 1);
+        zoom.percent_step = Some(0.0);
+        assert!(zoom.validate("zoom").is_err());
+    }
+
+    #[test]
+    fn control_names_are_spelled_the_way_v4l2_ctl_lists_them() {
+        assert_eq!(v4l2_control_name("Absolute Pan"), "absolute_pan");
+        assert_eq!(v4l2_control_name(" Zoom, Absolute (x10) "), "zoom_absolute_x10");
+        assert_eq!(v4l2_control_name("pan_absolute"), "pan_absolute");
+    }
+
+    #[test]
+    fn aliased_controls_with_spaces_are_read_and_written() {
+        let camera = FakeCamera::new().without_control("pan_absolute").with_control("absolute_pan", -468000, 468000, 7200);
+        let mut config = CameraConfig::default();
+        config.pan.control = Some("Absolute Pan".to_string());
+        config.movement.zoom_adjust = false;
+        let mut controller = CameraController::with_executor(config, Arc::new(camera.clone()));
+        assert_eq!(controller.detect_unsupported_axes().unwrap(), []);
+        controller.sync_from_device().unwrap();
+        assert_eq!(controller.get_pan(), 7200);
+
+        controller.set_pan(1).unwrap();
+        let set = camera.commands().into_iter().rev().find(|args| args.iter().any(|arg| arg.contains("--set-ctrl"))).unwrap();
+        assert!(set.iter().any(|arg| arg.ends_with("absolute_pan=37200")), "{:?}", set);
+        assert_eq!(camera.value("absolute_pan"), Some(37200));
+
+        controller.move_to(0, 3600, 10).unwrap();
+        assert_eq!(
+            ["absolute_pan", "tilt_absolute", "zoom_absolute"].map(|name| camera.value(name)),
+            [Some(0), Some(3600), Some(10)]
+        );
+    }
+
+    #[test]
+    fn moves_smaller_than_the_deadband_are_not_sent() {
+        let camera = FakeCamera::new().with_control("pan_absolute", 0, 100, 97);
+        let mut config = CameraConfig::default();
+        config.pan = ControlConfig { min: 0, max: 100, step: 10, deadband: 5, ..config.pan };
+        config.movement.zoom_adjust = false;
+        let mut controller = CameraController::with_executor(config, Arc::new(camera.clone()));
+        controller.sync_from_device().unwrap();
+        let sent = camera.commands().len();
+
+        // Only 3 units are left before max, under the deadband of 5
+        let outcome = controller.set_pan(1).unwrap();
+        assert_eq!(outcome, MoveOutcome { requested: 107, achieved: 97, command_sent: false, clamped: true });
+        assert_eq!((controller.get_pan(), camera.commands().len()), (97, sent));
+        controller.set_pan(-1).unwrap();
+        assert_eq!((controller.get_pan(), camera.value("pan_absolute")), (87, Some(87)));
+    }
+
+    #[test]
+    fn move_outcomes_report_clamping_and_what_the_device_settled_on() {
+        let camera = FakeCamera::new().with_control("zoom_absolute", 0, 80, 0);
+        let config = CameraConfig { verify_writes: true, ..CameraConfig::default() };
+        let mut controller = CameraController::with_executor(config, Arc::new(camera.clone()));
+        controller.sync_from_device().unwrap();
+
+        // The config allows zoom up to 100; the camera stops at 80
+        let outcome = controller.set_axis(Axis::Zoom, 150).unwrap();
+        assert_eq!(outcome, MoveOutcome { requested: 150, achieved: 80, command_sent: true, clamped: true });
+        let outcome = controller.set_axis(Axis::Zoom, 80).unwrap();
+        assert_eq!(outcome, MoveOutcome { requested: 80, achieved: 80, command_sent: false, clamped: false });
+        let outcome = controller.set_pan(-1).unwrap();
+        assert_eq!((outcome.achieved, outcome.command_sent, outcome.clamped), (outcome.requested, true, false));
+        assert_eq!(Some(outcome.achieved), camera.value("pan_absolute"));
+    }
+
+    #[test]
+    fn includes_merge_under_the_including_file() {
+        let dir = std::env::temp_dir().join(format!("tiny-ptz-include-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("shared")).unwrap();
+        std::fs::write(dir.join("shared/common.toml"), "include = [\"speeds.toml\"]\n[pan]\nstep = 7200\nmax = 100000\n").unwrap();
+        std::fs::write(dir.join("shared/speeds.toml"), "[movement]\ndebounce_ms = 40\n[pan]\nstep = 1\n").unwrap();
+        let main = dir.join("config.toml");
+        std::fs::write(&main, "include = [\"shared/common.toml\"]\ndevice = \"/dev/video4\"\n[pan]\nmax = 200000\n").unwrap();
+
+        let (config, _) = CameraConfig::load(&main).unwrap();
+        assert_eq!(config.device, "/dev/video4");
+        assert_eq!((config.pan.step, config.pan.max), (7200, 200000));
+        assert_eq!(config.movement.debounce_ms, Some(40));
+
+        std::fs::write(dir.join("shared/speeds.toml"), "include = [\"common.toml\"]\n").unwrap();
+        let error = format!("{:#}", CameraConfig::load(&main).unwrap_err());
+        assert!(error.contains("Include cycle") && error.contains("speeds.toml -> "), "{}", error);
+        std::fs::write(&main, "include = [\"missing.toml\"]\n").unwrap();
+        let error = format!("{:#}", CameraConfig::load(&main).unwrap_err());
+        assert!(error.contains("missing.toml not found"), "{}", error);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn zoom_display_interpolates_the_multiplier_table() {
+        let config: CameraConfig = toml::from_str("[zoom.display]\ntable = [[0, 1], [50, 2.0], [100, 5.0]]").unwrap();
+        let display = config.zoom.display.as_ref().unwrap();
+        assert_eq!(display.multiplier(-5), Some(1.0));
+        assert_eq!(display.multiplier(25), Some(1.5));
+        assert_eq!(display.multiplier(50), Some(2.0));
+        assert_eq!(display.multiplier(90), Some(4.4));
+        assert_eq!(display.multiplier(120), Some(5.0));
+        assert_eq!(ZoomDisplay::default().multiplier(50), None);
+        config.validate().unwrap();
+
+        for table in ["[]", "[[50, 2.0], [50, 3.0]]", "[[0, 0.0]]"] {
+            let config: CameraConfig = toml::from_str(&format!("[zoom.display]\ntable = {}", table)).unwrap();
+            let error = format!("{:#}", config.validate().unwrap_err());
+            assert!(error.contains("[zoom.display]"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn adaptive_interval_follows_latency_within_bounds() {
+        let mut movement = MovementConfig::default();
+        let mut latency = CommandLatency::default();
+        latency.record(Duration::from_millis(100));
+        assert_eq!(movement.adaptive_interval(&latency), None);
+
+        movement.adaptive_interval = true;
+        assert_eq!(movement.adaptive_interval(&CommandLatency::default()), None);
+        assert_eq!(movement.adaptive_interval(&latency), Some(Duration::from_millis(120)));
+        latency.record(Duration::from_secs(2));
+        assert_eq!(movement.adaptive_interval(&latency), Some(Duration::from_millis(500)));
+        let mut fast = CommandLatency::default();
+        fast.record(Duration::from_millis(1));
+        assert_eq!(movement.adaptive_interval(&fast), Some(Duration::from_millis(25)));
+
+        let mut config = CameraConfig::default();
+        config.movement.adaptive_min_ms = 600;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn latency_averages_a_rolling_window() {
+        let mut latency = CommandLatency::default();
+        assert_eq!((latency.last(), latency.average()), (None, None));
+        for ms in 1..=LATENCY_WINDOW as u64 + 2 {
+            latency.record(Duration::from_millis(ms * 10));
+        }
+        assert_eq!(latency.samples(), LATENCY_WINDOW);
+        assert_eq!(latency.last(), Some(Duration::from_millis(220)));
+        assert_eq!(latency.average(), Some(Duration::from_millis(125)));
+        assert!(!latency.is_slow());
+        latency.record(SLOW_COMMAND_THRESHOLD + Duration::from_millis(1));
+        assert!(latency.is_slow());
+    }
+
+    #[test]
+    fn config_serializes_to_toml_that_loads_back() {
+        let text = "\
+motion_profile = \"fast\"
+
+[zoom]
+max_step = 4
+
+[keys]
+zoom_in = \"Ctrl+Up\"
+
+[hooks]
+F2 = \"notify-send hi\"
+
+[ui.theme]
+title = \"#ff8800\"
+
+[profile.fast]
+pan_step = 60000
+";
+        let config: CameraConfig = toml::from_str(text).unwrap();
+        let printed = toml::to_string(&config).unwrap();
+        let reloaded: CameraConfig = toml::from_str(&printed).unwrap();
+        assert_eq!(toml::to_string(&reloaded).unwrap(), printed);
+        assert_eq!(reloaded.zoom.max_step, Some(4));
+        assert_eq!(reloaded.profile["fast"].pan_step, Some(60000));
+        #[cfg(feature = "tui")]
+        assert_eq!(reloaded.ui.theme.title, ratatui::style::Color::Rgb(0xff, 0x88, 0x00));
+        assert!(printed.contains("zoom_in = \"Ctrl+Up\""), "{}", printed);
+        assert!(printed.contains("F2 = \"notify-send hi\""), "{}", printed);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected_by_name() {
+        for (text, key) in [
+            ("tillt = 3", "tillt"),
+            ("[tilt]\nstpe = 3600", "stpe"),
+            ("[video.profile.smooth]\nargs = []\narg = []", "arg"),
+            ("[ui.theme]\ntitel = \"red\"", "titel"),
+        ]
+        .into_iter()
+        // Without the TUI, `[ui]` isn't checked
+        .filter(|(text, _)| cfg!(feature = "tui") || !text.starts_with("[ui"))
+        {
+            let error = toml::from_str::<CameraConfig>(text).unwrap_err().to_string();
+            assert!(error.contains(&format!("unknown field `{}`", key)), "{}", error);
+        }
+    }
+
+    #[test]
+    fn lenient_parsing_falls_back_per_section() {
+        let text = "device = \"/dev/video2\"\nmotion_profile = \"fats\"\n\n[pan]\nstep = \"big\"\n\n[tilt]\nmin = 10\nmax = -10\n\n[zoom]\nmax = 500\n\n[profile.fast]\npan_step = 60000\n";
+        assert!(CameraConfig::parse(text, "config.toml", None).is_err());
+        let (config, warnings) = CameraConfig::parse_lenient(text, "config.toml", None);
+        assert_eq!((config.device.as_str(), config.zoom.max), ("/dev/video2", 500));
+        assert_eq!((config.pan.step, config.tilt.min), (CameraConfig::default().pan.step, CameraConfig::default().tilt.min));
+        assert!(config.profile.contains_key("fast"));
+        assert_eq!(config.motion_profile, None);
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        for section in ["[pan]", "[tilt]", "`motion_profile`"] {
+            assert!(warnings.iter().any(|w| w.ends_with(&format!("using the defaults for {}", section))), "{:?}", warnings);
+        }
+
+        let (config, warnings) = CameraConfig::parse_lenient("[pan\nstep = 1", "config.toml", None);
+        assert_eq!(config.pan.step, CameraConfig::default().pan.step);
+        assert!(warnings[0].ends_with("using the built-in defaults"), "{:?}", warnings);
+    }
+
+    #[test]
+    fn step_can_be_a_percentage_of_the_range() {
+        assert_eq!(step_from_percent("1%", -468000, 468000).unwrap(), 9360);
+        assert_eq!(step_from_percent(" 12.5 % ", 0, 100).unwrap(), 13);
+        assert!(step_from_percent("0.1%", 0, 100).is_err());
+        assert!(step_from_percent("-5%", 0, 100).is_err());
+        assert!(step_from_percent("5", 0, 100).is_err());
+
+        let parse = |text: &str| CameraConfig::parse(text, "config.toml", None);
+        let config = parse("[pan]\nmin = -468000\nmax = 468000\nstep = \"1%\"\n[zoom]\nstep = \"10%\"\n").unwrap();
+        assert_eq!((config.pan.step, config.zoom.step), (9360, 10));
+        let error = format!("{:#}", parse("[tilt]\nmax = 10\nstep = \"1%\"\n").unwrap_err());
+        assert!(error.contains("[tilt]") && error.contains("at least 1"), "{}", error);
+        // Without a percentage, a typo is still reported at its own line
+        let error = format!("{:#}", parse("[pan]\nstep = 3600\nstpe = 1\n").unwrap_err());
+        assert!(error.contains("line 3") && error.contains("stpe"), "{}", error);
+
+        let (config, warnings) = CameraConfig::parse_lenient("[tilt]\nmax = 10\nstep = \"1%\"\n[zoom]\nstep = \"10%\"\n", "config.toml", None);
+        assert_eq!((config.tilt.max, config.zoom.step), (CameraConfig::default().tilt.max, 10));
+        assert!(warnings[0].contains("using the defaults for [tilt]"), "{:?}", warnings);
+    }
+
+    #[test]
+    fn capture_device_defaults_to_the_control_device() {
+        let mut config: CameraConfig = toml::from_str("device = \"/dev/video0\"").unwrap();
+        assert_eq!(config.capture_device(), "/dev/video0");
+        config.video.capture_device = Some("/dev/video2".to_string());
+        assert_eq!(config.capture_device(), "/dev/video2");
+        assert_eq!(config.device, "/dev/video0");
+    }
+
+    #[test]
+    fn missing_config_fields_use_defaults() {
+        let config: CameraConfig = toml::from_str("device = \"/dev/video2\"\n[zoom]\nmax = 500\n").unwrap();
+        assert_eq!(config.device, "/dev/video2");
+        assert_eq!(config.pan.max, CameraConfig::default().pan.max);
+        assert_eq!((config.zoom.min, config.zoom.max, config.zoom.step), (0, 500, 1));
+        assert!(config.video.profile.contains_key("low_latency"));
+
+        // An empty file is all defaults
+        let path = std::env::temp_dir().join(format!("tiny-ptz-empty-config-{}.toml", std::process::id()));
+        std::fs::write(&path, "\n").unwrap();
+        let (config, _) = CameraConfig::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(config.device, CameraConfig::default().device);
+    }
+
+    #[test]
+    fn style_follows_reported_version() {
+        assert_eq!(CtrlArgStyle::from_version_output("v4l2-ctl 1.22.1\n"), CtrlArgStyle::Separate);
+        assert_eq!(CtrlArgStyle::from_version_output("v4l2-ctl 0.9.5\n"), CtrlArgStyle::Joined);
+        assert_eq!(CtrlArgStyle::from_version_output(""), CtrlArgStyle::Separate);
+    }
+}
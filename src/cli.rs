@@ -0,0 +1,204 @@
+// src/cli.rs
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result, bail};
+
+use crate::camera::Axis;
+
+pub const USAGE: &str = "\
+Usage: tiny-ptz [OPTIONS] [COMMAND]
+
+Commands:
+  get [AXIS...]      Print the camera's position (every axis by default) and exit
+  set AXIS=VALUE...  Move the given axes, e.g. `set pan=3600 zoom=40`, and exit
+  look NAME          Apply the image controls in looks/NAME.toml and exit
+  panorama --steps N --out DIR
+                     Pan from min to max in N steps, saving a snapshot at each into DIR
+  selftest           Check the device, v4l2-ctl, each control and the player; exits nonzero on failure
+  track              Read `PAN TILT ZOOM` targets from stdin, one per line, and follow them
+                     (gliding with movement.smooth_move_ms, coalescing with movement.debounce_ms)
+
+Options:
+  --config PATH    Read the config from PATH instead of ./config.toml; `-` reads it from stdin
+  --no-altscreen   Draw inline instead of on the alternate screen
+  --detect         Print the camera's PTZ ranges as config.toml and exit
+  --print-config   Print the effective config as TOML and exit (with --detect, merged with the device's ranges)
+  --oneline        Print the position as one line on every change, without the TUI
+  --pick-device    Choose the camera from a list (also shown if the configured device is missing)
+  --profile NAME   Start with the [profile.NAME] motion settings
+  --lenient        Start with defaults for config settings that don't parse or validate, warning about each
+  --plain          Text-only display without colours or gauges, for screen readers
+  --events PATH    Write newline-delimited JSON status events to PATH (a file or named pipe)
+  --session-log PATH  Append the timestamped status history to PATH on quit
+  -v, --verbose    Show a debug strip with v4l2-ctl call timings
+  -h, --help       Print this help";
+
+/// One-shot commands that run without the TUI
+#[derive(Debug, Clone, PartialEq)]
+pub enum Subcommand {
+    Get(Vec<Axis>),
+    Set(Vec<(Axis, i32)>),
+    Look(String),
+    SelfTest,
+    Track,
+    Panorama { steps: u32, out: PathBuf },
+}
+
+/// Parses a `set` argument such as `pan=3600`
+fn parse_assignment(arg: &str) -> Result<(Axis, i32)> {
+    let Some((axis, value)) = arg.split_once('=') else {
+        bail!("Expected AXIS=VALUE, got '{}'", arg);
+    };
+    let value = value.parse().with_context(|| format!("Invalid value in '{}'", arg))?;
+    Ok((axis.parse()?, value))
+}
+
+/// Options accepted on the command line
+#[derive(Debug, Default)]
+pub struct Args {
+    /// Config file to load, `-` for stdin; `config.toml` when unset
+    pub config: Option<PathBuf>,
+    /// Keep output in the normal scrollback instead of the alternate screen
+    pub no_altscreen: bool,
+    /// Query the device's control ranges, print them and exit
+    pub detect: bool,
+    /// Print the fully resolved config and exit
+    pub print_config: bool,
+    /// Monitor the position as a single updating line instead of running the TUI
+    pub oneline: bool,
+    /// Choose the camera from `v4l2-ctl --list-devices` before starting
+    pub pick_device: bool,
+    pub command: Option<Subcommand>,
+    /// Motion profile to start with, in place of `motion_profile` in the config
+    pub profile: Option<String>,
+    /// Substitute defaults for bad config settings instead of refusing to start
+    pub lenient: bool,
+    /// Show the debug strip
+    pub verbose: bool,
+    /// Text-only display
+    pub plain: bool,
+    /// File the status history is appended to on quit
+    pub session_log: Option<PathBuf>,
+    /// File or named pipe JSON events are written to while the TUI runs
+    pub events: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Args {
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => match args.next() {
+                    Some(path) => parsed.config = Some(PathBuf::from(path)),
+                    None => bail!("--config needs a PATH (or - for stdin)\n\n{}", USAGE),
+                },
+                "--no-altscreen" => parsed.no_altscreen = true,
+                "--detect" => parsed.detect = true,
+                "--print-config" => parsed.print_config = true,
+                "--oneline" => parsed.oneline = true,
+                "--pick-device" => parsed.pick_device = true,
+                "--profile" => match args.next() {
+                    Some(name) => parsed.profile = Some(name),
+                    None => bail!("--profile needs a NAME\n\n{}", USAGE),
+                },
+                "--plain" => parsed.plain = true,
+                "--lenient" => parsed.lenient = true,
+                "--events" => match args.next() {
+                    Some(path) => parsed.events = Some(PathBuf::from(path)),
+                    None => bail!("--events needs a PATH\n\n{}", USAGE),
+                },
+                "--session-log" => match args.next() {
+                    Some(path) => parsed.session_log = Some(PathBuf::from(path)),
+                    None => bail!("--session-log needs a PATH\n\n{}", USAGE),
+                },
+                "-v" | "--verbose" => parsed.verbose = true,
+                "-h" | "--help" => parsed.help = true,
+                // Everything after a subcommand is its arguments
+                "get" => {
+                    let axes = args.by_ref().map(|a| a.parse()).collect::<Result<_>>()?;
+                    parsed.command = Some(Subcommand::Get(axes));
+                }
+                "look" => match (args.next(), args.next()) {
+                    (Some(name), None) => parsed.command = Some(Subcommand::Look(name)),
+                    _ => bail!("look needs exactly one NAME\n\n{}", USAGE),
+                },
+                "selftest" => parsed.command = Some(Subcommand::SelfTest),
+                "track" => parsed.command = Some(Subcommand::Track),
+                "panorama" => {
+                    let (mut steps, mut out) = (None, None);
+                    while let Some(flag) = args.next() {
+                        match (flag.as_str(), args.next()) {
+                            ("--steps", Some(n)) => {
+                                steps = Some(n.parse().with_context(|| format!("Invalid --steps '{}'", n))?)
+                            }
+                            ("--out", Some(dir)) => out = Some(PathBuf::from(dir)),
+                            _ => bail!("panorama takes --steps N and --out DIR\n\n{}", USAGE),
+                        }
+                    }
+                    match (steps, out) {
+                        (Some(steps @ 2..), Some(out)) => parsed.command = Some(Subcommand::Panorama { steps, out }),
+                        (Some(_), Some(_)) => bail!("panorama needs at least 2 steps"),
+                        _ => bail!("panorama needs both --steps N and --out DIR\n\n{}", USAGE),
+                    }
+                }
+                "set" => {
+                    let assignments: Vec<_> = args.by_ref().map(|a| parse_assignment(&a)).collect::<Result<_>>()?;
+                    if assignments.is_empty() {
+                        bail!("set needs at least one AXIS=VALUE\n\n{}", USAGE);
+                    }
+                    parsed.command = Some(Subcommand::Set(assignments));
+                }
+                other => bail!("Unknown argument '{}'\n\n{}", other, USAGE),
+            }
+        }
+        // Both would read stdin: the config to its end, leaving track no targets
+        if parsed.command == Some(Subcommand::Track) && parsed.config.as_deref() == Some(Path::new("-")) {
+            bail!("track reads its targets from stdin, so it can't take --config -");
+        }
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args> {
+        Args::parse_from(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parses_subcommands() {
+        assert_eq!(parse(&["get"]).unwrap().command, Some(Subcommand::Get(vec![])));
+        assert_eq!(parse(&["get", "zoom"]).unwrap().command, Some(Subcommand::Get(vec![Axis::Zoom])));
+        assert_eq!(
+            parse(&["set", "pan=-3600", "zoom=40"]).unwrap().command,
+            Some(Subcommand::Set(vec![(Axis::Pan, -3600), (Axis::Zoom, 40)]))
+        );
+        assert!(parse(&["set"]).is_err());
+        assert!(parse(&["set", "roll=1"]).is_err());
+        assert!(parse(&["set", "pan=left"]).is_err());
+        assert_eq!(parse(&["selftest"]).unwrap().command, Some(Subcommand::SelfTest));
+        assert_eq!(parse(&["track"]).unwrap().command, Some(Subcommand::Track));
+        assert!(parse(&["--config", "-", "track"]).is_err());
+        assert_eq!(
+            parse(&["panorama", "--out", "shots", "--steps", "5"]).unwrap().command,
+            Some(Subcommand::Panorama { steps: 5, out: PathBuf::from("shots") })
+        );
+        assert!(parse(&["panorama", "--steps", "1", "--out", "shots"]).is_err());
+        assert!(parse(&["panorama", "--steps", "5"]).is_err());
+    }
+
+    #[test]
+    fn config_path_defaults_to_unset() {
+        assert_eq!(parse(&[]).unwrap().config, None);
+        assert_eq!(parse(&["--config", "-", "get"]).unwrap().config, Some(PathBuf::from("-")));
+        assert!(parse(&["--config"]).is_err());
+        assert!(parse(&["--lenient"]).unwrap().lenient);
+    }
+}
@@ -0,0 +1,212 @@
+// src/detect.rs
+use std::fmt::Write as _;
+use anyhow::{Result, bail};
+
+use crate::backend::Executor;
+use crate::camera::{Axis, CameraConfig};
+
+/// Range of an integer control as reported by `v4l2-ctl --list-ctrls`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlRange {
+    pub name: String,
+    pub min: i32,
+    pub max: i32,
+    pub step: i32,
+    pub default: Option<i32>,
+}
+
+impl ControlRange {
+    /// Number of device steps between `min` and `max`
+    pub fn steps(&self) -> i64 {
+        (self.max as i64 - self.min as i64) / self.step.max(1) as i64
+    }
+}
+
+/// A capture node listed by `v4l2-ctl --list-devices`
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoDevice {
+    /// The camera's name, e.g. "HD Pro Webcam C920 (usb-0000:00:14.0-1)"
+    pub name: String,
+    pub node: String,
+}
+
+/// Lists the cameras on the system. A failing `v4l2-ctl` (as happens when there are
+/// no devices at all) yields whatever it managed to print, usually nothing.
+pub fn list_devices(executor: &dyn Executor) -> Result<Vec<VideoDevice>> {
+    let output = executor.run(&["--list-devices".to_string()])?;
+    Ok(parse_device_list(&output.stdout))
+}
+
+/// Parses `--list-devices` output: an unindented camera name followed by its indented
+/// device nodes. Only `/dev/video*` nodes are kept.
+pub fn parse_device_list(output: &str) -> Vec<VideoDevice> {
+    let mut devices = Vec::new();
+    let mut name = None;
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        if !line.starts_with(char::is_whitespace) {
+            name = Some(line.trim().trim_end_matches(':').to_string());
+        } else if let Some(name) = &name {
+            let node = line.trim();
+            if node.starts_with("/dev/video") {
+                devices.push(VideoDevice { name: name.clone(), node: node.to_string() });
+            }
+        }
+    }
+    devices
+}
+
+/// Queries every control the device exposes
+pub fn query_ranges(executor: &dyn Executor, device: &str) -> Result<Vec<ControlRange>> {
+    let output = executor.run(&["-d".to_string(), device.to_string(), "--list-ctrls".to_string()])?;
+
+    if !output.success {
+        bail!("Error listing controls on {}: {}", device, output.stderr);
+    }
+
+    Ok(parse_ranges(&output.stdout))
+}
+
+/// Parses lines such as
+/// `pan_absolute 0x009a0908 (int) : min=-468000 max=468000 step=3600 default=0 value=0`.
+/// Lines without `min`/`max`/`step` (menus, booleans, headings) are skipped.
+pub fn parse_ranges(output: &str) -> Vec<ControlRange> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (head, fields) = line.split_once(" : ")?;
+            let name = head.split_whitespace().next()?.to_string();
+            let field = |key: &str| {
+                fields
+                    .split_whitespace()
+                    .find_map(|kv| kv.strip_prefix(key)?.strip_prefix('=')?.parse::<i32>().ok())
+            };
+            Some(ControlRange {
+                name,
+                min: field("min")?,
+                max: field("max")?,
+                step: field("step")?,
+                default: field("default"),
+            })
+        })
+        .collect()
+}
+
+/// Adopts the device's PTZ ranges (and defaults, where reported) into `config`.
+/// Axes whose control isn't reported are marked unsupported.
+pub fn apply_ranges(config: &mut CameraConfig, ranges: &[ControlRange]) {
+    for axis in Axis::ALL {
+        let name = config.control_name(axis);
+        let control = config.control_mut(axis);
+        match ranges.iter().find(|range| range.name == name) {
+            Some(range) => {
+                control.min = range.min;
+                control.max = range.max;
+                control.step = range.step;
+                control.default = range.default.or(control.default);
+            }
+            None => control.supported = false,
+        }
+    }
+}
+
+/// Renders the PTZ ranges as a `config.toml` snippet, annotated with step counts
+/// and, where `units_per_degree` is configured, the span in degrees
+pub fn render_config(device: &str, ranges: &[ControlRange], config: &CameraConfig) -> String {
+    let mut out = format!("device = \"{}\"\n", device);
+    for (axis, units_per_degree) in [
+        (Axis::Pan, config.pan.units_per_degree),
+        (Axis::Tilt, config.tilt.units_per_degree),
+        (Axis::Zoom, None),
+    ] {
+        let (section, control) = (axis.name(), config.control_name(axis));
+        let Some(range) = ranges.iter().find(|r| r.name == control) else {
+            let _ = writeln!(out, "\n# [{}]: {} not reported by this camera", section, control);
+            continue;
+        };
+        let _ = writeln!(out, "\n[{}]", section);
+        let _ = write!(out, "# {} steps of {}", range.steps(), range.step);
+        if let Some(units) = units_per_degree {
+            let _ = write!(
+                out,
+                ", {:.1}° to {:.1}° ({:.2}° per step)",
+                range.min as f64 / units,
+                range.max as f64 / units,
+                range.step as f64 / units
+            );
+        }
+        let _ = writeln!(out);
+        if control != axis.control() {
+            let _ = writeln!(out, "control = \"{}\"", control);
+        }
+        let _ = writeln!(out, "min = {}\nmax = {}\nstep = {}", range.min, range.max, range.step);
+        if let Some(default) = range.default {
+            let _ = writeln!(out, "default = {}", default);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST_CTRLS: &str = "\
+Camera Controls
+
+                   pan_absolute 0x009a0908 (int)    : min=-468000 max=468000 step=3600 default=0 value=0
+                  tilt_absolute 0x009a0909 (int)    : min=-324000 max=324000 step=3600 default=0 value=0
+         focus_automatic_continuous 0x009a090c (bool)   : default=1 value=1
+                  zoom_absolute 0x009a090d (int)    : min=0 max=100 step=1 default=0 value=0
+";
+
+    #[test]
+    fn parses_integer_controls_and_skips_the_rest() {
+        let ranges = parse_ranges(LIST_CTRLS);
+        let names: Vec<&str> = ranges.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["pan_absolute", "tilt_absolute", "zoom_absolute"]);
+        assert_eq!(
+            ranges[0],
+            ControlRange { name: "pan_absolute".into(), min: -468000, max: 468000, step: 3600, default: Some(0) }
+        );
+        assert_eq!(ranges[0].steps(), 260);
+    }
+
+    #[test]
+    fn lists_video_nodes_per_camera() {
+        let output = "\
+HD Pro Webcam C920 (usb-0000:00:14.0-1):
+\t/dev/video0
+\t/dev/video1
+\t/dev/media0
+
+Integrated Camera (usb-0000:00:14.0-5):
+\t/dev/video2
+";
+        let devices = parse_device_list(output);
+        let nodes: Vec<&str> = devices.iter().map(|d| d.node.as_str()).collect();
+        assert_eq!(nodes, ["/dev/video0", "/dev/video1", "/dev/video2"]);
+        assert_eq!(devices[0].name, "HD Pro Webcam C920 (usb-0000:00:14.0-1)");
+        assert_eq!(devices[2].name, "Integrated Camera (usb-0000:00:14.0-5)");
+        assert!(parse_device_list("").is_empty());
+    }
+
+    #[test]
+    fn rendered_config_round_trips() {
+        let rendered = render_config("/dev/video0", &parse_ranges(LIST_CTRLS), &CameraConfig::default());
+        assert!(rendered.contains("# 260 steps of 3600"));
+        let config: CameraConfig = toml::from_str(&rendered).unwrap();
+        assert_eq!((config.pan.min, config.pan.max, config.pan.step), (-468000, 468000, 3600));
+        assert_eq!(config.zoom.default, Some(0));
+    }
+
+    #[test]
+    fn applied_ranges_replace_limits_and_disable_missing_axes() {
+        let mut config = CameraConfig::default();
+        let ranges: Vec<ControlRange> =
+            parse_ranges(LIST_CTRLS).into_iter().filter(|r| r.name != "tilt_absolute").collect();
+        apply_ranges(&mut config, &ranges);
+        assert_eq!((config.pan.min, config.pan.max, config.pan.step), (-468000, 468000, 3600));
+        assert_eq!((config.zoom.step, config.zoom.default), (1, Some(0)));
+        assert!(!config.tilt.supported);
+    }
+}
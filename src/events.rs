@@ -0,0 +1,204 @@
+// src/events.rs
+//! `--events PATH`: newline-delimited JSON status events for external tools, such as
+//! `{"event":"position","pan":3600,"tilt":0,"zoom":40}`, written while the TUI runs.
+use anyhow::Context;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{broadcast, mpsc};
+
+use crate::app::App;
+use crate::camera::Position;
+
+/// One line of the events stream, tagged by its `event` field
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// The controller moved, from its broadcast channel
+    Position(Position),
+    /// A new status message
+    Status(String),
+    /// A status message reporting a failure
+    Error(String),
+    /// The motion profile or the video preview changed
+    Mode { motion_profile: Option<String>, preview: bool },
+}
+
+impl Event {
+    /// The event as a single-line JSON object
+    pub fn to_json(&self) -> String {
+        match self {
+            Event::Position(position) => format!(
+                "{{\"event\":\"position\",\"pan\":{},\"tilt\":{},\"zoom\":{}}}",
+                position.pan, position.tilt, position.zoom
+            ),
+            Event::Status(message) => format!("{{\"event\":\"status\",\"message\":{}}}", json_string(message)),
+            Event::Error(message) => format!("{{\"event\":\"error\",\"message\":{}}}", json_string(message)),
+            Event::Mode { motion_profile, preview } => format!(
+                "{{\"event\":\"mode\",\"motion_profile\":{},\"preview\":{}}}",
+                motion_profile.as_deref().map_or_else(|| "null".to_string(), json_string),
+                preview
+            ),
+        }
+    }
+}
+
+/// `s` as a quoted JSON string
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Spots status and mode changes in the app between UI updates. Positions come from the
+/// controller's broadcast channel instead; see `forward_positions`.
+#[derive(Debug, Default)]
+pub struct EventWatcher {
+    status: Option<(String, bool)>,
+    mode: Option<(Option<String>, bool)>,
+}
+
+impl EventWatcher {
+    /// Events for whatever changed since the last call; everything counts as changed on the first
+    pub fn changes(&mut self, app: &App) -> Vec<Event> {
+        let mut events = Vec::new();
+        let status = (app.status_message.clone(), app.status_is_error());
+        if self.status.as_ref() != Some(&status) {
+            let (message, is_error) = status.clone();
+            events.push(if is_error { Event::Error(message) } else { Event::Status(message) });
+            self.status = Some(status);
+        }
+        let mode = (app.motion_profile().map(str::to_string), app.preview_running());
+        if self.mode.as_ref() != Some(&mode) {
+            events.push(Event::Mode { motion_profile: mode.0.clone(), preview: mode.1 });
+            self.mode = Some(mode);
+        }
+        events
+    }
+}
+
+/// Passes every position the controller publishes on to the events writer. A lagging
+/// receiver skips to the newest positions rather than stopping.
+pub async fn forward_positions(mut positions: broadcast::Receiver<Position>, events: mpsc::Sender<Event>) {
+    loop {
+        match positions.recv().await {
+            Ok(position) => {
+                if events.send(Event::Position(position)).await.is_err() {
+                    return;
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+/// Appends each event to `path` as one line, flushing after every line so a reader on a
+/// named pipe sees events as they happen. Opening a pipe waits for its reader. Fails if
+/// `path` can't be opened or a write fails, e.g. when the pipe's reader goes away.
+pub async fn write_events(path: PathBuf, mut events: mpsc::Receiver<Event>) -> anyhow::Result<()> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    while let Some(event) = events.recv().await {
+        let line = format!("{}\n", event.to_json());
+        let written = async {
+            file.write_all(line.as_bytes()).await?;
+            file.flush().await
+        };
+        written.await.with_context(|| format!("Failed to write to {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::app::{AppPaths, InputEvent};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use crate::camera::CameraConfig;
+    use crate::fake::FakeCamera;
+
+    #[test]
+    fn events_are_tagged_single_line_json() {
+        assert_eq!(
+            Event::Position(Position { pan: 3600, tilt: -7200, zoom: 40 }).to_json(),
+            r#"{"event":"position","pan":3600,"tilt":-7200,"zoom":40}"#
+        );
+        assert_eq!(
+            Event::Error("Error: \"busy\"\nretry".to_string()).to_json(),
+            r#"{"event":"error","message":"Error: \"busy\"\nretry"}"#
+        );
+        assert_eq!(
+            Event::Mode { motion_profile: None, preview: true }.to_json(),
+            r#"{"event":"mode","motion_profile":null,"preview":true}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn positions_and_status_changes_reach_the_file() {
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(FakeCamera::new()), AppPaths::scratch());
+        let path = std::env::temp_dir().join(format!("tiny-ptz-events-{}.ndjson", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let (tx, rx) = mpsc::channel(16);
+        let writer = tokio::spawn(write_events(path.clone(), rx));
+        let forwarder = tokio::spawn(forward_positions(app.camera_controller.subscribe(), tx.clone()));
+
+        let mut watcher = EventWatcher::default();
+        assert_eq!(watcher.changes(&app).len(), 2);
+        app.camera_controller.set_pan(1).unwrap();
+        app.set_error("Camera /dev/video0 disconnected".to_string());
+        for event in watcher.changes(&app) {
+            tx.send(event).await.unwrap();
+        }
+        assert!(watcher.changes(&app).is_empty());
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        drop(app);
+        drop(tx);
+        forwarder.await.unwrap();
+        writer.await.unwrap().unwrap();
+
+        let lines = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(lines.contains(r#"{"event":"position","pan":30000,"tilt":0,"zoom":0}"#), "{}", lines);
+        assert!(lines.contains(r#"{"event":"error","message":"Camera /dev/video0 disconnected"}"#), "{}", lines);
+    }
+
+    #[test]
+    fn a_video_launch_failure_is_an_error_event() {
+        let mut app = App::with_paths(CameraConfig::default(), Arc::new(FakeCamera::new()), AppPaths::scratch());
+        let mut watcher = EventWatcher::default();
+        watcher.changes(&app);
+        std::thread::sleep(app.command_interval());
+        app.update(InputEvent::Key(KeyEvent::new(KeyCode::Char('v'), KeyModifiers::NONE)));
+        let events = watcher.changes(&app);
+        assert!(events.iter().any(|event| event.to_json().starts_with(r#"{"event":"error","message":"Failed to start video feed"#)), "{:?}", events);
+        app.status_message = "Video feed stopped.".to_string();
+        assert_eq!(watcher.changes(&app), vec![Event::Status("Video feed stopped.".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn a_path_that_cannot_be_opened_is_an_error_naming_it() {
+        let path = std::env::temp_dir().join("tiny-ptz-no-such-dir").join("events.ndjson");
+        let (_tx, rx) = mpsc::channel(1);
+        let error = write_events(path.clone(), rx).await.unwrap_err();
+        assert_eq!(error.to_string(), format!("Failed to open {}", path.display()));
+    }
+}
@@ -0,0 +1,147 @@
+// src/fake.rs
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io;
+use std::process::{Child, Stdio};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::backend::{CtlOutput, Executor};
+
+/// One integer control of a `FakeCamera`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FakeControl {
+    pub min: i32,
+    pub max: i32,
+    pub value: i32,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    controls: BTreeMap<String, FakeControl>,
+    log: Vec<Vec<String>>,
+    /// `--set-ctrl` calls still to be acknowledged without changing anything
+    dropped_writes: usize,
+}
+
+/// An in-memory camera that answers `v4l2-ctl` invocations: it clamps writes to each
+/// control's range like real hardware and records every command it receives.
+/// Clones share state, so a test can keep one handle and give another to the controller.
+#[derive(Debug, Clone, Default)]
+pub struct FakeCamera {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeCamera {
+    /// Pan, tilt and zoom with the ranges of `CameraConfig::default()`, centred with zoom at 0
+    pub fn new() -> Self {
+        FakeCamera::default()
+            .with_control("pan_absolute", -468000, 468000, 0)
+            .with_control("tilt_absolute", -324000, 324000, 0)
+            .with_control("zoom_absolute", 0, 100, 0)
+    }
+
+    pub fn with_control(self, name: &str, min: i32, max: i32, value: i32) -> Self {
+        self.state().controls.insert(name.to_string(), FakeControl { min, max, value });
+        self
+    }
+
+    pub fn without_control(self, name: &str) -> Self {
+        self.state().controls.remove(name);
+        self
+    }
+
+    /// Makes the next `count` writes report success but leave the controls where they are,
+    /// like a camera that misses commands
+    pub fn dropping_writes(self, count: usize) -> Self {
+        self.state().dropped_writes = count;
+        self
+    }
+
+    pub fn value(&self, name: &str) -> Option<i32> {
+        self.state().controls.get(name).map(|control| control.value)
+    }
+
+    /// Every invocation so far, excluding the `--version` probe
+    pub fn commands(&self) -> Vec<Vec<String>> {
+        self.state().log.iter().filter(|args| args[..] != ["--version"]).cloned().collect()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn fail(out: &mut CtlOutput, message: String) {
+    out.success = false;
+    let _ = writeln!(out.stderr, "{}", message);
+}
+
+impl Executor for FakeCamera {
+    fn run(&self, args: &[String]) -> io::Result<CtlOutput> {
+        let mut state = self.state();
+        state.log.push(args.to_vec());
+        let mut out = CtlOutput { success: true, ..CtlOutput::default() };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-d" => {
+                    args.next();
+                }
+                "--version" => out.stdout.push_str("v4l2-ctl 1.22.1\n"),
+                "--list-ctrls" => {
+                    for (name, control) in &state.controls {
+                        let _ = writeln!(
+                            out.stdout,
+                            "{:>30} 0x00000000 (int)    : min={} max={} step=1 default=0 value={}",
+                            name, control.min, control.max, control.value
+                        );
+                    }
+                }
+                "--get-ctrl" => {
+                    for name in args.next().map(String::as_str).unwrap_or_default().split(',') {
+                        match state.controls.get(name) {
+                            Some(control) => {
+                                let _ = writeln!(out.stdout, "{}: {}", name, control.value);
+                            }
+                            None => fail(&mut out, format!("unknown control '{}'", name)),
+                        }
+                    }
+                }
+                "--set-ctrl" if state.dropped_writes > 0 => {
+                    state.dropped_writes -= 1;
+                    args.next();
+                }
+                "--set-ctrl" => {
+                    // Like v4l2-ctl, controls before a bad one in the list are still applied
+                    for assignment in args.next().map(String::as_str).unwrap_or_default().split(',') {
+                        let parsed = assignment
+                            .split_once('=')
+                            .and_then(|(name, value)| Some((name, value.parse::<i32>().ok()?)));
+                        match parsed.and_then(|(name, value)| Some((state.controls.get_mut(name)?, value))) {
+                            Some((control, value)) => control.value = value.clamp(control.min, control.max),
+                            None => {
+                                fail(&mut out, format!("invalid control assignment '{}'", assignment));
+                                break;
+                            }
+                        }
+                    }
+                }
+                other => fail(&mut out, format!("unrecognized option '{}'", other)),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Records the hook as `sh -c <command>` without running it
+    fn shell(&self, command: &str) -> io::Result<CtlOutput> {
+        self.state().log.push(vec!["sh".to_string(), "-c".to_string(), command.to_string()]);
+        Ok(CtlOutput { success: true, ..CtlOutput::default() })
+    }
+
+    /// Records the preview as `ffplay <args>` and reports the player missing, so tests
+    /// never open a window
+    fn spawn_player(&self, args: &[String], _stderr: Stdio) -> io::Result<Child> {
+        self.state().log.push(["ffplay".to_string()].into_iter().chain(args.iter().cloned()).collect());
+        Err(io::Error::new(io::ErrorKind::NotFound, "the fake camera has no video player"))
+    }
+}
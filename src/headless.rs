@@ -0,0 +1,480 @@
+// src/headless.rs
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result, bail};
+
+use crate::camera::{Axis, CameraConfig, CameraController, MovementConfig, SmoothMove};
+use crate::cli::Subcommand;
+use crate::detect;
+use crate::looks::{self, LOOKS_DIR};
+
+/// How often `--oneline` polls the device
+const ONELINE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often `track` sends the next point of a glide, like the TUI's default command interval
+const TRACK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Resolves on SIGINT or, on unix, SIGTERM
+pub async fn wait_for_shutdown_signal() -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result,
+            _ = terminate.recv() => Ok(()),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await
+    }
+}
+
+/// Formats a position as a single status line, e.g. `pan=3600 tilt=0 zoom=50%`
+pub fn oneline_status(config: &CameraConfig, pan: i32, tilt: i32, zoom: i32) -> String {
+    let zoom_range = (config.zoom.max - config.zoom.min).max(1) as f64;
+    let zoom_percent = ((zoom - config.zoom.min) as f64 / zoom_range * 100.0).round();
+    format!("pan={} tilt={} zoom={}%", pan, tilt, zoom_percent)
+}
+
+/// `--oneline`: polls the device and prints the position whenever it changes, until SIGINT.
+/// On a terminal the line is rewritten in place; through a pipe each change is its own line.
+pub async fn run_oneline(config: CameraConfig) -> Result<()> {
+    let mut controller = CameraController::new(config);
+    let _ = controller.detect_unsupported_axes();
+    let in_place = io::stdout().is_terminal();
+    let mut last_line = String::new();
+    let mut interval = tokio::time::interval(ONELINE_POLL_INTERVAL);
+    let shutdown = wait_for_shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = interval.tick() => {}
+        }
+        let line = match controller.sync_from_device() {
+            Ok(()) => oneline_status(
+                &controller.config,
+                controller.get_pan(),
+                controller.get_tilt(),
+                controller.get_zoom(),
+            ),
+            Err(e) => format!("error: {}", e.to_string().trim()),
+        };
+        if line != last_line {
+            let mut stdout = io::stdout().lock();
+            if in_place {
+                // Clear the rest of the previous line in case the new one is shorter
+                write!(stdout, "\r{}\x1b[K", line)?;
+            } else {
+                writeln!(stdout, "{}", line)?;
+            }
+            stdout.flush()?;
+            last_line = line;
+        }
+    }
+
+    if in_place {
+        println!();
+    }
+    Ok(())
+}
+
+/// Parses a `track` line of three whitespace-separated values, `PAN TILT ZOOM`
+pub fn parse_target(line: &str) -> Result<[i32; 3]> {
+    let values: Vec<&str> = line.split_whitespace().collect();
+    let [pan, tilt, zoom] = values[..] else {
+        bail!("Expected PAN TILT ZOOM, got '{}'", line.trim());
+    };
+    let parse = |value: &str| value.parse().with_context(|| format!("Invalid value '{}' in '{}'", value, line.trim()));
+    Ok([parse(pan)?, parse(tilt)?, parse(zoom)?])
+}
+
+/// Follows a stream of targets for `track`. A new target waits until none has come for
+/// `debounce_ms`, the latest replacing any still waiting, then the camera glides there
+/// from wherever it is over `smooth_move_ms` (or jumps without it).
+#[derive(Debug, Clone)]
+pub struct Tracker {
+    /// The latest target and when it came, until it's quiet long enough to start
+    pending: Option<([i32; 3], Instant)>,
+    glide: Option<SmoothMove>,
+    quiet: Duration,
+    glide_time: Duration,
+}
+
+impl Tracker {
+    pub fn new(movement: &MovementConfig) -> Self {
+        Tracker {
+            pending: None,
+            glide: None,
+            quiet: Duration::from_millis(movement.debounce_ms.unwrap_or(0)),
+            glide_time: Duration::from_millis(movement.smooth_move_ms.unwrap_or(0)),
+        }
+    }
+
+    pub fn set_target(&mut self, target: [i32; 3], now: Instant) {
+        self.pending = Some((target, now));
+    }
+
+    /// Starts a settled target and sends the next point of the glide, if any
+    pub fn tick(&mut self, controller: &mut CameraController, now: Instant) -> Result<()> {
+        if let Some((target, _)) = self.pending.filter(|(_, at)| now.duration_since(*at) >= self.quiet) {
+            self.pending = None;
+            let from = Axis::ALL.map(|axis| controller.position_of(axis));
+            self.glide = Some(SmoothMove::new(from, target, now, self.glide_time));
+        }
+        let Some(glide) = self.glide else {
+            return Ok(());
+        };
+        let (position, arrived) = glide.position_at(now);
+        if arrived {
+            self.glide = None;
+        }
+        controller.move_to(position[0], position[1], position[2])
+    }
+
+    /// Whether every target so far has been reached
+    pub fn is_idle(&self) -> bool {
+        self.pending.is_none() && self.glide.is_none()
+    }
+}
+
+/// `track`: follows `PAN TILT ZOOM` lines from stdin until SIGINT, or until stdin closes
+/// and the last target is reached. Bad lines are reported on stderr and skipped.
+pub async fn run_track(config: CameraConfig) -> Result<()> {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut tracker = Tracker::new(&config.movement);
+    let mut controller = CameraController::new(config);
+    let _ = controller.detect_unsupported_axes();
+    controller.sync_from_device()?;
+    let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    let mut input_open = true;
+    let mut interval = tokio::time::interval(TRACK_INTERVAL);
+    let shutdown = wait_for_shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            line = lines.next_line(), if input_open => match line {
+                // The bad line has been consumed, so reading carries on after it
+                Err(e) if e.kind() == std::io::ErrorKind::InvalidData => eprintln!("track: skipped a line: {}", e),
+                Err(e) => return Err(e.into()),
+                Ok(Some(line)) if line.trim().is_empty() => {}
+                Ok(Some(line)) => match parse_target(&line) {
+                    Ok(target) => tracker.set_target(target, Instant::now()),
+                    Err(e) => eprintln!("track: {}", e),
+                },
+                Ok(None) => input_open = false,
+            },
+            _ = interval.tick() => {
+                if let Err(e) = tracker.tick(&mut controller, Instant::now()) {
+                    eprintln!("track: {}", e.to_string().trim());
+                }
+                if !input_open && tracker.is_idle() {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Formats axis values as `pan=3600 tilt=0 zoom=40`
+fn format_values(values: &[(Axis, i32)]) -> String {
+    values
+        .iter()
+        .map(|(axis, value)| format!("{}={}", axis.name(), value))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Runs a `get`/`set` subcommand and prints the resulting values. `track` runs on
+/// the async runtime instead, through `run_track`.
+pub fn run_subcommand(config: CameraConfig, command: Subcommand) -> Result<()> {
+    let mut controller = CameraController::new(config);
+    match command {
+        Subcommand::Get(axes) => {
+            let axes = if axes.is_empty() { Axis::ALL.to_vec() } else { axes };
+            let controls: Vec<String> = axes.iter().map(|axis| controller.config.control_name(*axis)).collect();
+            let values: Vec<(Axis, i32)> = controller
+                .read_values(&controls.iter().map(String::as_str).collect::<Vec<_>>())?
+                .into_iter()
+                .filter_map(|(name, value)| Some((controller.config.axis_for_control(&name)?, value)))
+                .collect();
+            println!("{}", format_values(&values));
+        }
+        Subcommand::Set(assignments) => {
+            // Start from the real position so only the named axes are sent
+            controller.sync_from_device()?;
+            let mut target = Axis::ALL.map(|axis| controller.position_of(axis));
+            for (axis, value) in &assignments {
+                target[*axis as usize] = *value;
+            }
+            controller.move_to(target[0], target[1], target[2])?;
+            let values: Vec<(Axis, i32)> =
+                assignments.iter().map(|(axis, _)| (*axis, controller.position_of(*axis))).collect();
+            println!("{}", format_values(&values));
+        }
+        Subcommand::Look(name) => {
+            let look = looks::load_look(std::path::Path::new(LOOKS_DIR), &name)?;
+            controller.set_controls(&look.controls)?;
+            println!("{}", look.summary());
+        }
+        Subcommand::SelfTest => {
+            let mut checks = self_test(&mut controller);
+            checks.push(player_check());
+            for check in &checks {
+                match &check.failure {
+                    None => println!("PASS  {}", check.name),
+                    Some(hint) => println!("FAIL  {}\n      {}", check.name, hint),
+                }
+            }
+            let failed = checks.iter().filter(|check| check.failure.is_some()).count();
+            if failed > 0 {
+                bail!("{} of {} checks failed", failed, checks.len());
+            }
+            println!("All {} checks passed", checks.len());
+        }
+        Subcommand::Track => bail!("track follows stdin asynchronously; run it with run_track"),
+        Subcommand::Panorama { steps, out } => {
+            std::fs::create_dir_all(&out).with_context(|| format!("Failed to create {}", out.display()))?;
+            let device = controller.config.capture_device().to_string();
+            let shots = sweep_panorama(&mut controller, steps, &out, |path| capture_snapshot(&device, path))?;
+            for shot in shots {
+                println!("{}", shot.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `steps` pan values spread evenly from `min` to `max`, both included
+pub fn panorama_positions(min: i32, max: i32, steps: u32) -> Vec<i32> {
+    let span = max as i64 - min as i64;
+    let last = steps.saturating_sub(1).max(1) as i64;
+    (0..steps as i64).map(|i| (min as i64 + span * i / last) as i32).collect()
+}
+
+/// Pans across the full range in `steps` moves, waiting `settle_ms` after each then calling `capture` with the
+/// file for each (`pan_000.jpg`, ...), and finally returns to the starting position, even
+/// if a move or capture failed. Returns the files captured.
+pub fn sweep_panorama(
+    controller: &mut CameraController,
+    steps: u32,
+    out: &Path,
+    mut capture: impl FnMut(&Path) -> Result<()>,
+) -> Result<Vec<PathBuf>> {
+    if !controller.config.pan.supported {
+        bail!("panorama needs pan, which this camera doesn't support");
+    }
+    controller.sync_from_device()?;
+    let start = Axis::ALL.map(|axis| controller.position_of(axis));
+    let settle = Duration::from_millis(controller.config.settle_ms);
+    let mut shots = Vec::new();
+    let mut sweep = || -> Result<()> {
+        for (i, pan) in panorama_positions(controller.config.pan.min, controller.config.pan.max, steps).into_iter().enumerate() {
+            // move_to returns once v4l2-ctl has, so the settle time starts from the finished command
+            controller.move_to(pan, start[1], start[2])?;
+            std::thread::sleep(settle);
+            let shot = out.join(format!("pan_{:03}.jpg", i));
+            capture(&shot).with_context(|| format!("Failed to capture {}", shot.display()))?;
+            shots.push(shot);
+        }
+        Ok(())
+    };
+    let swept = sweep();
+    let returned = controller.move_to(start[0], start[1], start[2]).context("Failed to return to the starting position");
+    swept.and(returned).map(|()| shots)
+}
+
+/// Saves one frame from `device` to `path` with ffmpeg
+fn capture_snapshot(device: &str, path: &Path) -> Result<()> {
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-y", "-f", "v4l2", "-i", device, "-frames:v", "1"])
+        .arg(path)
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run ffmpeg; install ffmpeg to capture snapshots")?;
+    if !output.status.success() {
+        bail!("ffmpeg: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// One line of the `selftest` report
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: String,
+    /// What went wrong and how to fix it; `None` if the check passed
+    pub failure: Option<String>,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, result: std::result::Result<(), String>) -> Self {
+        Check { name: name.into(), failure: result.err() }
+    }
+}
+
+/// The device checks behind `selftest`: `v4l2-ctl` runs, the device answers, and each
+/// supported axis can be nudged one device step and put back. Stops at the first check
+/// the later ones depend on.
+pub fn self_test(controller: &mut CameraController) -> Vec<Check> {
+    let executor = controller.executor();
+    let device = controller.config.device.clone();
+    let mut checks = Vec::new();
+
+    let version = match executor.run(&["--version".to_string()]) {
+        Ok(output) if output.success => Ok(()),
+        Ok(output) => Err(format!("v4l2-ctl --version failed: {}", output.stderr.trim())),
+        Err(e) => Err(format!("{}; install v4l-utils, which provides v4l2-ctl", e)),
+    };
+    checks.push(Check::new("v4l2-ctl is installed", version));
+    if checks.iter().any(|check| check.failure.is_some()) {
+        return checks;
+    }
+
+    let ranges = match detect::query_ranges(executor.as_ref(), &device) {
+        Ok(ranges) => ranges,
+        Err(e) => {
+            let hint = format!("{}; check `device` in config.toml or choose one with --pick-device", e.to_string().trim());
+            checks.push(Check::new(format!("{} responds", device), Err(hint)));
+            return checks;
+        }
+    };
+    checks.push(Check::new(format!("{} responds", device), Ok(())));
+
+    // An axis the camera doesn't list fails here and is left out of the checks below
+    for axis in controller.supported_axes() {
+        let control_name = controller.config.control_name(axis);
+        if !ranges.iter().any(|range| range.name == control_name) {
+            let hint = format!("the camera doesn't report it; set `supported = false` under [{}]", axis.name());
+            checks.push(Check::new(format!("{} exists", control_name), Err(hint)));
+            controller.config.control_mut(axis).supported = false;
+        }
+    }
+
+    let position = controller.sync_from_device().map_err(|e| {
+        format!("{}; another program may be holding the camera", e.to_string().trim())
+    });
+    let synced = position.is_ok();
+    checks.push(Check::new("position can be read", position));
+    if !synced {
+        return checks;
+    }
+
+    for axis in controller.supported_axes() {
+        let control_name = controller.config.control_name(axis);
+        let name = format!("{} can be set (one step and back)", control_name);
+        let original = controller.position_of(axis);
+        let direction = if original >= controller.config.control(axis).max { -1 } else { 1 };
+        let result = controller
+            .nudge_device_step(axis, direction)
+            .and_then(|_| controller.set_controls(&[(control_name, original)]))
+            .map_err(|e| format!("{}; check the [{}] limits against --detect", e.to_string().trim(), axis.name()));
+        checks.push(Check::new(name, result));
+    }
+    checks
+}
+
+/// Whether the preview player can be started at all
+fn player_check() -> Check {
+    let result = match Command::new("ffplay").arg("-version").output() {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("{}; install ffmpeg, which provides ffplay, for the video preview", e)),
+    };
+    Check::new("ffplay is installed", result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::fake::FakeCamera;
+
+    #[test]
+    fn self_test_nudges_each_axis_and_puts_it_back() {
+        let camera = FakeCamera::new().with_control("zoom_absolute", 0, 100, 100);
+        let mut controller = CameraController::with_executor(CameraConfig::default(), Arc::new(camera.clone()));
+        let checks = self_test(&mut controller);
+        assert!(checks.iter().all(|check| check.failure.is_none()), "{:?}", checks);
+        assert_eq!(checks.len(), 6);
+        assert_eq!((camera.value("pan_absolute"), camera.value("zoom_absolute")), (Some(0), Some(100)));
+        // Zoom sits at its maximum, so it is nudged down rather than up
+        assert!(camera.commands().iter().any(|args| args.last().map(String::as_str) == Some("zoom_absolute=99")));
+    }
+
+    #[test]
+    fn panorama_spans_the_range_and_returns_to_the_start() {
+        assert_eq!(panorama_positions(-468000, 468000, 3), [-468000, 0, 468000]);
+        assert_eq!(panorama_positions(0, 10, 4), [0, 3, 6, 10]);
+
+        let camera = FakeCamera::new().with_control("pan_absolute", -468000, 468000, 36000);
+        let config = CameraConfig { settle_ms: 0, ..CameraConfig::default() };
+        let mut controller = CameraController::with_executor(config, Arc::new(camera.clone()));
+        let mut captured = Vec::new();
+        let shots = sweep_panorama(&mut controller, 3, Path::new("pano"), |path| {
+            captured.push((path.to_path_buf(), camera.value("pan_absolute")));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(shots, [Path::new("pano/pan_000.jpg"), Path::new("pano/pan_001.jpg"), Path::new("pano/pan_002.jpg")]);
+        let pans: Vec<Option<i32>> = captured.iter().map(|(_, pan)| *pan).collect();
+        assert_eq!(pans, [Some(-468000), Some(0), Some(468000)]);
+        assert_eq!(camera.value("pan_absolute"), Some(36000));
+
+        let error = sweep_panorama(&mut controller, 3, Path::new("pano"), |_| bail!("no frame")).unwrap_err();
+        assert!(format!("{:#}", error).contains("no frame"));
+        assert_eq!(camera.value("pan_absolute"), Some(36000));
+    }
+
+    #[test]
+    fn track_targets_parse_from_three_values() {
+        assert_eq!(parse_target(" 3600\t-7200 40 ").unwrap(), [3600, -7200, 40]);
+        assert!(parse_target("3600 0").is_err());
+        assert!(parse_target("3600 0 wide").is_err());
+    }
+
+    #[test]
+    fn tracker_coalesces_quick_targets_and_glides_to_the_last() {
+        let camera = FakeCamera::new();
+        let movement = MovementConfig { debounce_ms: Some(50), smooth_move_ms: Some(1000), ..MovementConfig::default() };
+        let mut controller = CameraController::with_executor(CameraConfig::default(), Arc::new(camera.clone()));
+        controller.sync_from_device().unwrap();
+        let mut tracker = Tracker::new(&movement);
+        let start = Instant::now();
+        tracker.set_target([100000, 0, 0], start);
+        tracker.set_target([400000, -40000, 50], start + Duration::from_millis(30));
+        tracker.tick(&mut controller, start + Duration::from_millis(60)).unwrap();
+        assert_eq!(camera.value("pan_absolute"), Some(0)); // Still waiting out the debounce
+
+        let began = start + Duration::from_millis(80);
+        tracker.tick(&mut controller, began).unwrap();
+        tracker.tick(&mut controller, began + Duration::from_millis(500)).unwrap();
+        assert_eq!(
+            ["pan_absolute", "tilt_absolute", "zoom_absolute"].map(|name| camera.value(name)),
+            [Some(200000), Some(-20000), Some(25)]
+        );
+        tracker.tick(&mut controller, began + Duration::from_secs(1)).unwrap();
+        assert_eq!(camera.value("pan_absolute"), Some(400000));
+        assert!(tracker.is_idle());
+    }
+
+    #[test]
+    fn self_test_reports_a_missing_control_with_a_hint() {
+        let camera = FakeCamera::new().without_control("tilt_absolute");
+        let mut controller = CameraController::with_executor(CameraConfig::default(), Arc::new(camera));
+        let checks = self_test(&mut controller);
+        let failed: Vec<&Check> = checks.iter().filter(|check| check.failure.is_some()).collect();
+        assert_eq!(failed.len(), 1, "{:?}", checks);
+        assert_eq!(failed[0].name, "tilt_absolute exists");
+        assert!(failed[0].failure.as_deref().unwrap().contains("supported = false"));
+        // Pan and zoom are still read and nudged
+        assert_eq!(checks.len(), 6);
+    }
+}
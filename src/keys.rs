@@ -0,0 +1,721 @@
+// src/keys.rs
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+use anyhow::{Result, anyhow, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::camera::Axis;
+
+/// Everything a key can be bound to. Config names are the snake_case variant names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    PanLeft,
+    PanRight,
+    TiltUp,
+    TiltDown,
+    NudgeLeft,
+    NudgeRight,
+    NudgeUp,
+    NudgeDown,
+    JumpLeft,
+    JumpRight,
+    JumpUp,
+    JumpDown,
+    JumpZoomIn,
+    JumpZoomOut,
+    NextZoomStop,
+    ZoomIn,
+    ZoomOut,
+    ToggleVideo,
+    CycleVideoProfile,
+    Bookmark,
+    NextBookmark,
+    PeekBookmark,
+    ReturnToPrevious,
+    PresetsPanel,
+    CopyPosition,
+    NextLook,
+    ResetDefaults,
+    ToggleSpeedInfo,
+    CycleTheme,
+    FasterCommands,
+    SlowerCommands,
+    CycleMotionProfile,
+    ToggleLock,
+    ToggleArrowZoom,
+    RepeatLast,
+    Quit,
+    QuitWithoutSaving,
+}
+
+impl Action {
+    /// Every action, in the order the Help panel lists them
+    pub const ALL: [Action; 37] = [
+        Action::PanLeft,
+        Action::PanRight,
+        Action::TiltUp,
+        Action::TiltDown,
+        Action::NudgeLeft,
+        Action::NudgeRight,
+        Action::NudgeUp,
+        Action::NudgeDown,
+        Action::JumpLeft,
+        Action::JumpRight,
+        Action::JumpUp,
+        Action::JumpDown,
+        Action::JumpZoomIn,
+        Action::JumpZoomOut,
+        Action::NextZoomStop,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ToggleVideo,
+        Action::CycleVideoProfile,
+        Action::Bookmark,
+        Action::NextBookmark,
+        Action::PeekBookmark,
+        Action::ReturnToPrevious,
+        Action::PresetsPanel,
+        Action::CopyPosition,
+        Action::NextLook,
+        Action::ResetDefaults,
+        Action::ToggleSpeedInfo,
+        Action::CycleTheme,
+        Action::FasterCommands,
+        Action::SlowerCommands,
+        Action::CycleMotionProfile,
+        Action::ToggleLock,
+        Action::ToggleArrowZoom,
+        Action::RepeatLast,
+        Action::Quit,
+        Action::QuitWithoutSaving,
+    ];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::PanLeft => "Pan left",
+            Action::PanRight => "Pan right",
+            Action::TiltUp => "Tilt up",
+            Action::TiltDown => "Tilt down",
+            Action::NudgeLeft => "Pan left one device step",
+            Action::NudgeRight => "Pan right one device step",
+            Action::NudgeUp => "Tilt up one device step",
+            Action::NudgeDown => "Tilt down one device step",
+            Action::JumpLeft => "Pan to the left limit",
+            Action::JumpRight => "Pan to the right limit",
+            Action::JumpUp => "Tilt to the upper limit",
+            Action::JumpDown => "Tilt to the lower limit",
+            Action::JumpZoomIn => "Zoom all the way in",
+            Action::JumpZoomOut => "Zoom all the way out",
+            Action::NextZoomStop => "Zoom to the next [zoom] stop",
+            Action::ZoomIn => "Zoom in",
+            Action::ZoomOut => "Zoom out",
+            Action::ToggleVideo => "Toggle video feed",
+            Action::CycleVideoProfile => "Cycle video profile",
+            Action::Bookmark => "Bookmark current position",
+            Action::NextBookmark => "Go to next bookmark",
+            Action::PeekBookmark => "Show the next bookmark without moving",
+            Action::ReturnToPrevious => "Return to the position before the last jump",
+            Action::PresetsPanel => "List/edit saved presets",
+            Action::CopyPosition => "Copy position",
+            Action::NextLook => "Apply next look",
+            Action::ResetDefaults => "Reset to config defaults",
+            Action::ToggleSpeedInfo => "Show/hide speed info",
+            Action::CycleTheme => "Cycle theme",
+            Action::FasterCommands => "Command interval -25ms",
+            Action::SlowerCommands => "Command interval +25ms",
+            Action::CycleMotionProfile => "Cycle motion profile",
+            Action::ToggleLock => "Lock/unlock the camera against moves",
+            Action::ToggleArrowZoom => "Switch up/down between tilt and zoom",
+            Action::RepeatLast => "Repeat the last step move",
+            Action::Quit => "Quit",
+            Action::QuitWithoutSaving => "Quit without saving",
+        }
+    }
+
+    /// Whether the action sends a command to the camera (as opposed to app-only actions)
+    pub fn is_camera_command(self) -> bool {
+        matches!(
+            self,
+            Action::PanLeft
+                | Action::PanRight
+                | Action::TiltUp
+                | Action::TiltDown
+                | Action::NudgeLeft
+                | Action::NudgeRight
+                | Action::NudgeUp
+                | Action::NudgeDown
+                | Action::JumpLeft
+                | Action::JumpRight
+                | Action::JumpUp
+                | Action::JumpDown
+                | Action::JumpZoomIn
+                | Action::JumpZoomOut
+                | Action::NextZoomStop
+                | Action::ZoomIn
+                | Action::ZoomOut
+                | Action::NextBookmark
+                | Action::ReturnToPrevious
+                | Action::NextLook
+                | Action::ResetDefaults
+        )
+    }
+
+    /// Whether the action goes to an absolute position, which Return to previous can undo
+    pub fn is_jump(self) -> bool {
+        matches!(
+            self,
+            Action::JumpLeft
+                | Action::JumpRight
+                | Action::JumpUp
+                | Action::JumpDown
+                | Action::JumpZoomIn
+                | Action::JumpZoomOut
+                | Action::NextZoomStop
+                | Action::NextBookmark
+                | Action::ResetDefaults
+        )
+    }
+
+    /// The axis a movement action drives
+    pub fn axis(self) -> Option<Axis> {
+        match self {
+            Action::PanLeft | Action::PanRight | Action::NudgeLeft | Action::NudgeRight | Action::JumpLeft | Action::JumpRight => {
+                Some(Axis::Pan)
+            }
+            Action::TiltUp | Action::TiltDown | Action::NudgeUp | Action::NudgeDown | Action::JumpUp | Action::JumpDown => {
+                Some(Axis::Tilt)
+            }
+            Action::ZoomIn | Action::ZoomOut | Action::JumpZoomIn | Action::JumpZoomOut | Action::NextZoomStop => Some(Axis::Zoom),
+            _ => None,
+        }
+    }
+
+    /// Whether the action writes its own status message instead of the generic "Command sent."
+    pub fn sets_own_status(self) -> bool {
+        !matches!(
+            self,
+            Action::PanLeft
+                | Action::PanRight
+                | Action::TiltUp
+                | Action::TiltDown
+                | Action::NudgeLeft
+                | Action::NudgeRight
+                | Action::NudgeUp
+                | Action::NudgeDown
+                | Action::JumpLeft
+                | Action::JumpRight
+                | Action::JumpUp
+                | Action::JumpDown
+                | Action::JumpZoomIn
+                | Action::JumpZoomOut
+                | Action::ZoomIn
+                | Action::ZoomOut
+        )
+    }
+}
+
+/// A key plus modifiers, written in config as e.g. `"Shift+Up"`, `"Ctrl+p"`, `"v"` or `"["`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyDescriptor {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyDescriptor {
+    pub const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        KeyDescriptor { code, modifiers }
+    }
+
+    /// The key as written in `config.toml`, e.g. `"Ctrl+Up"`; parses back to the same key
+    pub fn config_name(&self) -> String {
+        let mut name = String::new();
+        for (modifier, prefix) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                name.push_str(prefix);
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => name.push_str("Space"),
+            KeyCode::Char(c) => name.push(c),
+            KeyCode::F(n) => name.push_str(&format!("F{}", n)),
+            KeyCode::PageUp => name.push_str("PageUp"),
+            KeyCode::PageDown => name.push_str("PageDown"),
+            other => name.push_str(&format!("{:?}", other)),
+        }
+        name
+    }
+
+    /// Modifiers must match exactly, except that Shift is implied by the case of a character
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let significant = |code: KeyCode, modifiers: KeyModifiers| match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        self.code == key.code
+            && significant(self.code, self.modifiers) == significant(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyDescriptor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // Split on '+' but keep a trailing '+' as the key itself ("Ctrl++")
+        let (modifier_part, key_part) = match s.strip_suffix('+') {
+            Some(rest) if rest.is_empty() || rest.ends_with('+') => (rest.trim_end_matches('+'), "+"),
+            _ => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in modifier_part.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "shift" => KeyModifiers::SHIFT,
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                other => bail!("Unknown modifier '{}' in key '{}'", other, s),
+            };
+        }
+
+        let mut chars = key_part.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                // "Shift+r" is the same keypress as "R"
+                modifiers -= KeyModifiers::SHIFT;
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key_part.to_lowercase().as_str() {
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "space" => KeyCode::Char(' '),
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                other => match other.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(anyhow!("Unknown key '{}'", s)),
+                },
+            },
+        };
+        Ok(KeyDescriptor { code, modifiers })
+    }
+}
+
+impl fmt::Display for KeyDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// The active keys for every action: the defaults, overridden by the `[keys]` config section
+/// (e.g. `pan_left = "h"`, or `pan_left = ["Left", "h"]` to keep both), plus any keys
+/// that step image controls (`image.brightness.increase = "B"`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "KeysSection", into = "KeysSection")]
+pub struct KeyBindings {
+    bindings: BTreeMap<Action, Vec<KeyDescriptor>>,
+    /// Actions bound in `[keys]` rather than by default
+    explicit: BTreeSet<Action>,
+    /// `image.<control>` tables as written, for saving the config back
+    image: BTreeMap<String, ImageKeys>,
+    /// The same, parsed: each control direction with its signed step and keys
+    image_steps: Vec<(String, i32, Vec<KeyDescriptor>)>,
+}
+
+/// The `[keys]` section as written: actions by config name, and `image.<control>` tables
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeysSection {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    image: BTreeMap<String, ImageKeys>,
+    #[serde(flatten)]
+    actions: BTreeMap<Action, KeyList>,
+}
+
+/// Keys for one image control, e.g. `[keys.image.brightness]` with `increase = "B"`,
+/// `decrease = "Ctrl+b"` and `step = 8`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImageKeys {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub increase: Option<KeyList>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decrease: Option<KeyList>,
+    /// Units each press moves the control
+    pub step: i32,
+}
+
+impl Default for ImageKeys {
+    fn default() -> Self {
+        ImageKeys { increase: None, decrease: None, step: 1 }
+    }
+}
+
+/// One `[keys]` entry: a single key, or a list of keys that all trigger the action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn parse(&self) -> Result<Vec<KeyDescriptor>> {
+        match self {
+            KeyList::One(key) => Ok(vec![key.parse()?]),
+            KeyList::Many(keys) => keys.iter().map(|key| key.parse()).collect(),
+        }
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        use KeyCode::*;
+        let none = KeyModifiers::NONE;
+        let shift = KeyModifiers::SHIFT;
+        let ctrl = KeyModifiers::CONTROL;
+        let alt = KeyModifiers::ALT;
+        KeyBindings {
+            explicit: BTreeSet::new(),
+            image: BTreeMap::new(),
+            image_steps: Vec::new(),
+            bindings: BTreeMap::from([
+                (Action::PanLeft, vec![KeyDescriptor::new(Left, none)]),
+                (Action::PanRight, vec![KeyDescriptor::new(Right, none)]),
+                (Action::TiltUp, vec![KeyDescriptor::new(Up, none)]),
+                (Action::TiltDown, vec![KeyDescriptor::new(Down, none)]),
+                (Action::NudgeLeft, vec![KeyDescriptor::new(Left, ctrl)]),
+                (Action::NudgeRight, vec![KeyDescriptor::new(Right, ctrl)]),
+                (Action::NudgeUp, vec![KeyDescriptor::new(Up, ctrl)]),
+                (Action::NudgeDown, vec![KeyDescriptor::new(Down, ctrl)]),
+                (Action::JumpLeft, vec![KeyDescriptor::new(Left, alt)]),
+                (Action::JumpRight, vec![KeyDescriptor::new(Right, alt)]),
+                (Action::JumpUp, vec![KeyDescriptor::new(Up, alt)]),
+                (Action::JumpDown, vec![KeyDescriptor::new(Down, alt)]),
+                (Action::JumpZoomIn, vec![KeyDescriptor::new(Up, alt.union(shift))]),
+                (Action::JumpZoomOut, vec![KeyDescriptor::new(Down, alt.union(shift))]),
+                (Action::NextZoomStop, vec![KeyDescriptor::new(Char('Z'), none)]),
+                (Action::ZoomIn, vec![KeyDescriptor::new(Up, shift)]),
+                (Action::ZoomOut, vec![KeyDescriptor::new(Down, shift)]),
+                (Action::ToggleVideo, vec![KeyDescriptor::new(Char('v'), none)]),
+                (Action::CycleVideoProfile, vec![KeyDescriptor::new(Char('p'), none)]),
+                (Action::Bookmark, vec![KeyDescriptor::new(Char('b'), none)]),
+                (Action::NextBookmark, vec![KeyDescriptor::new(Char('n'), none)]),
+                (Action::PeekBookmark, vec![KeyDescriptor::new(Char('N'), none)]),
+                (Action::ReturnToPrevious, vec![KeyDescriptor::new(Backspace, none)]),
+                (Action::PresetsPanel, vec![KeyDescriptor::new(Char('P'), none)]),
+                (Action::CopyPosition, vec![KeyDescriptor::new(Char('y'), none)]),
+                (Action::NextLook, vec![KeyDescriptor::new(Char('L'), none)]),
+                (Action::ResetDefaults, vec![KeyDescriptor::new(Char('R'), none)]),
+                (Action::ToggleSpeedInfo, vec![KeyDescriptor::new(Char('s'), none)]),
+                (Action::CycleTheme, vec![KeyDescriptor::new(Char('t'), none)]),
+                (Action::FasterCommands, vec![KeyDescriptor::new(Char('['), none)]),
+                (Action::SlowerCommands, vec![KeyDescriptor::new(Char(']'), none)]),
+                (Action::CycleMotionProfile, vec![KeyDescriptor::new(Char('m'), none)]),
+                (Action::ToggleLock, vec![KeyDescriptor::new(Char('l'), none)]),
+                (Action::ToggleArrowZoom, vec![KeyDescriptor::new(Char('z'), none)]),
+                (Action::RepeatLast, vec![KeyDescriptor::new(Enter, none)]),
+                (Action::Quit, vec![KeyDescriptor::new(Char('q'), none)]),
+                (Action::QuitWithoutSaving, vec![KeyDescriptor::new(Char('Q'), none)]),
+            ]),
+        }
+    }
+}
+
+impl TryFrom<KeysSection> for KeyBindings {
+    type Error = anyhow::Error;
+
+    fn try_from(section: KeysSection) -> Result<Self> {
+        let mut keys = KeyBindings::default();
+        for (action, list) in section.actions {
+            let descriptors = list.parse()?;
+            for key in &descriptors {
+                let event = KeyEvent::new(key.code, key.modifiers);
+                let taken = keys.explicit.iter().find(|other| keys.bindings[*other].iter().any(|d| d.matches(&event)));
+                if let Some(other) = taken {
+                    bail!("[keys] key '{}' is bound to both {:?} and {:?}", key, other, action);
+                }
+            }
+            keys.bindings.insert(action, descriptors);
+            keys.explicit.insert(action);
+        }
+        // A key given to an action here is taken from the action that has it by default
+        let claimed: Vec<KeyDescriptor> = keys.explicit.iter().flat_map(|action| keys.bindings[action].clone()).collect();
+        for key in &claimed {
+            keys.release_default(key);
+        }
+        for (control, image) in &section.image {
+            if image.step <= 0 {
+                bail!("[keys] image.{}.step must be positive, got {}", control, image.step);
+            }
+            for (direction, list, delta) in [("increase", &image.increase, image.step), ("decrease", &image.decrease, -image.step)] {
+                let Some(list) = list else {
+                    continue;
+                };
+                let descriptors = list.parse()?;
+                for key in &descriptors {
+                    if let Some(action) = keys.action_for(&KeyEvent::new(key.code, key.modifiers)) {
+                        bail!("[keys] image.{}.{} key '{}' is already bound to {:?}", control, direction, key, action);
+                    }
+                }
+                keys.image_steps.push((control.clone(), delta, descriptors));
+            }
+        }
+        keys.image = section.image;
+        Ok(keys)
+    }
+}
+
+impl From<KeyBindings> for KeysSection {
+    fn from(keys: KeyBindings) -> Self {
+        let actions = keys
+            .bindings
+            .iter()
+            .map(|(action, descriptors)| {
+                let list = match &descriptors[..] {
+                    [key] => KeyList::One(key.config_name()),
+                    keys => KeyList::Many(keys.iter().map(KeyDescriptor::config_name).collect()),
+                };
+                (*action, list)
+            })
+            .collect();
+        KeysSection { image: keys.image, actions }
+    }
+}
+
+impl KeyBindings {
+    /// The action bound to a key press, if any
+    pub fn action_for(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, descriptors)| descriptors.iter().any(|descriptor| descriptor.matches(key)))
+            .map(|(action, _)| *action)
+    }
+
+    /// Whether `action`'s keys were set in `[keys]` rather than left at the defaults
+    pub fn is_explicit(&self, action: Action) -> bool {
+        self.explicit.contains(&action)
+    }
+
+    /// Takes `key` away from any action it is bound to only by default, so a `[hooks]`
+    /// entry for it runs instead
+    pub fn release_default(&mut self, key: &KeyDescriptor) {
+        for (action, descriptors) in &mut self.bindings {
+            if !self.explicit.contains(action) {
+                descriptors.retain(|descriptor| descriptor != key);
+            }
+        }
+    }
+
+    /// Makes Shift+Down zoom in and Shift+Up zoom out. Only applies while both zoom keys
+    /// are the defaults, so explicit `[keys]` zoom bindings win.
+    pub fn swap_default_zoom_keys(&mut self) {
+        let defaults = KeyBindings::default();
+        if [Action::ZoomIn, Action::ZoomOut].iter().all(|action| self.keys_for(*action) == defaults.keys_for(*action)) {
+            let zoom_in = self.bindings.remove(&Action::ZoomIn).unwrap_or_default();
+            let zoom_out = self.bindings.remove(&Action::ZoomOut).unwrap_or_default();
+            self.bindings.insert(Action::ZoomIn, zoom_out);
+            self.bindings.insert(Action::ZoomOut, zoom_in);
+        }
+    }
+
+    /// The first key bound to an action, as named in status messages
+    pub fn key_for(&self, action: Action) -> Option<KeyDescriptor> {
+        self.keys_for(action).first().copied()
+    }
+
+    /// Every key bound to an action, in config order
+    pub fn keys_for(&self, action: Action) -> &[KeyDescriptor] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// The image control a key steps and by how much, e.g. `("brightness", -8)`
+    pub fn image_step_for(&self, key: &KeyEvent) -> Option<(&str, i32)> {
+        self.image_steps
+            .iter()
+            .find(|(_, _, keys)| keys.iter().any(|descriptor| descriptor.matches(key)))
+            .map(|(control, delta, _)| (control.as_str(), *delta))
+    }
+
+    /// One "keys: description" line per bound action, for the Help panel, e.g. "←/h: Pan left",
+    /// followed by the image-control keys, e.g. "B: brightness +8"
+    pub fn help_lines(&self) -> Vec<String> {
+        let join = |keys: &[KeyDescriptor]| keys.iter().map(KeyDescriptor::to_string).collect::<Vec<_>>().join("/");
+        let actions = Action::ALL
+            .iter()
+            .filter(|action| !self.keys_for(**action).is_empty())
+            .map(|action| format!("{}: {}", join(self.keys_for(*action)), action.description()));
+        let image = self.image_steps.iter().map(|(control, delta, keys)| format!("{}: {} {:+}", join(keys), control, delta));
+        actions.chain(image).collect()
+    }
+}
+
+/// The `[hooks]` section: keys that run a shell command, e.g. `"Ctrl+l" = "lights toggle"`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(try_from = "BTreeMap<String, String>", into = "BTreeMap<String, String>")]
+pub struct Hooks {
+    hooks: Vec<(KeyDescriptor, String)>,
+}
+
+impl TryFrom<BTreeMap<String, String>> for Hooks {
+    type Error = anyhow::Error;
+
+    fn try_from(hooks: BTreeMap<String, String>) -> Result<Self> {
+        let hooks = hooks
+            .into_iter()
+            .map(|(key, command)| Ok((key.parse()?, command)))
+            .collect::<Result<_>>()?;
+        Ok(Hooks { hooks })
+    }
+}
+
+impl From<Hooks> for BTreeMap<String, String> {
+    fn from(hooks: Hooks) -> Self {
+        hooks.hooks.iter().map(|(key, command)| (key.config_name(), command.clone())).collect()
+    }
+}
+
+impl Hooks {
+    /// The command bound to a key press, if any
+    pub fn command_for(&self, key: &KeyEvent) -> Option<&str> {
+        self.hooks
+            .iter()
+            .find(|(descriptor, _)| descriptor.matches(key))
+            .map(|(_, command)| command.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(KeyDescriptor, String)> {
+        self.hooks.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses_and_displays_descriptors() {
+        for (text, shown) in [("Shift+Up", "Shift+↑"), ("ctrl+p", "Ctrl+p"), ("Shift+r", "R"), ("[", "["), ("F5", "F5")] {
+            assert_eq!(text.parse::<KeyDescriptor>().unwrap().to_string(), shown);
+        }
+        for text in ["Ctrl+Up", "Alt+Shift+Left", "R", "Space", "Ctrl++", "PageUp", "F12", "Esc"] {
+            assert_eq!(text.parse::<KeyDescriptor>().unwrap().config_name(), text);
+        }
+        assert!("Hyper+x".parse::<KeyDescriptor>().is_err());
+        assert!("Nope".parse::<KeyDescriptor>().is_err());
+    }
+
+    #[test]
+    fn default_bindings_keep_shift_for_zoom() {
+        let keys = KeyBindings::default();
+        assert_eq!(keys.action_for(&press(KeyCode::Up, KeyModifiers::NONE)), Some(Action::TiltUp));
+        assert_eq!(keys.action_for(&press(KeyCode::Up, KeyModifiers::SHIFT)), Some(Action::ZoomIn));
+        assert_eq!(keys.action_for(&press(KeyCode::Char('R'), KeyModifiers::SHIFT)), Some(Action::ResetDefaults));
+    }
+
+    #[test]
+    fn zoom_keys_swap_unless_remapped() {
+        let mut keys = KeyBindings::default();
+        keys.swap_default_zoom_keys();
+        assert_eq!(keys.action_for(&press(KeyCode::Down, KeyModifiers::SHIFT)), Some(Action::ZoomIn));
+        assert!(keys.help_lines().contains(&"Shift+↑: Zoom out".to_string()));
+
+        let mut remapped: KeyBindings = toml::from_str("zoom_in = \"+\"").unwrap();
+        remapped.swap_default_zoom_keys();
+        assert_eq!(remapped.action_for(&press(KeyCode::Down, KeyModifiers::SHIFT)), Some(Action::ZoomOut));
+    }
+
+    #[test]
+    fn config_overrides_single_actions() {
+        let keys: KeyBindings = toml::from_str("pan_left = \"h\"\npan_right = \"l\"").unwrap();
+        assert_eq!(keys.action_for(&press(KeyCode::Char('h'), KeyModifiers::NONE)), Some(Action::PanLeft));
+        assert_eq!(keys.action_for(&press(KeyCode::Left, KeyModifiers::NONE)), None);
+        assert!(keys.help_lines().contains(&"h: Pan left".to_string()));
+        assert!(keys.help_lines().contains(&"Shift+↑: Zoom in".to_string()));
+    }
+
+    #[test]
+    fn a_key_belongs_to_one_action() {
+        // Taking a default key moves it, rather than leaving the old action unreachable behind it
+        let keys: KeyBindings = toml::from_str("pan_right = [\"Right\", \"l\"]").unwrap();
+        assert_eq!(keys.action_for(&press(KeyCode::Char('l'), KeyModifiers::NONE)), Some(Action::PanRight));
+        assert_eq!(keys.key_for(Action::ToggleLock), None);
+        assert!(!keys.help_lines().iter().any(|line| line.starts_with("l: ")));
+
+        let error = toml::from_str::<KeyBindings>("pan_right = \"l\"\ntoggle_lock = \"l\"").unwrap_err().to_string();
+        assert!(error.contains("key 'l' is bound to both PanRight and ToggleLock"), "{}", error);
+    }
+
+    #[test]
+    fn actions_accept_a_list_of_keys() {
+        let keys: KeyBindings = toml::from_str("pan_left = [\"Left\", \"h\"]\nquit = []").unwrap();
+        assert_eq!(keys.action_for(&press(KeyCode::Left, KeyModifiers::NONE)), Some(Action::PanLeft));
+        assert_eq!(keys.action_for(&press(KeyCode::Char('h'), KeyModifiers::NONE)), Some(Action::PanLeft));
+        assert_eq!(keys.action_for(&press(KeyCode::Char('q'), KeyModifiers::NONE)), None);
+        assert_eq!(keys.key_for(Action::PanLeft), Some(KeyDescriptor::new(KeyCode::Left, KeyModifiers::NONE)));
+        assert!(keys.help_lines().contains(&"←/h: Pan left".to_string()));
+        assert!(!keys.help_lines().iter().any(|line| line.ends_with(": Quit")));
+
+        let written = toml::to_string(&keys).unwrap();
+        assert!(written.contains("pan_left = [\"Left\", \"h\"]"), "{}", written);
+        assert!(written.contains("pan_right = \"Right\""), "{}", written);
+        let reread: KeyBindings = toml::from_str(&written).unwrap();
+        assert_eq!(reread.keys_for(Action::PanLeft), keys.keys_for(Action::PanLeft));
+    }
+
+    #[test]
+    fn image_controls_take_their_own_keys() {
+        let keys: KeyBindings =
+            toml::from_str("pan_left = \"h\"\nimage.brightness.increase = \"B\"\nimage.brightness.decrease = [\"Ctrl+b\", \"F5\"]\nimage.brightness.step = 8")
+                .unwrap();
+        assert_eq!(keys.image_step_for(&press(KeyCode::Char('B'), KeyModifiers::SHIFT)), Some(("brightness", 8)));
+        assert_eq!(keys.image_step_for(&press(KeyCode::F(5), KeyModifiers::NONE)), Some(("brightness", -8)));
+        assert_eq!(keys.image_step_for(&press(KeyCode::Char('h'), KeyModifiers::NONE)), None);
+        assert!(keys.help_lines().contains(&"Ctrl+b/F5: brightness -8".to_string()));
+
+        let written = toml::to_string(&keys).unwrap();
+        let reread: KeyBindings = toml::from_str(&written).unwrap();
+        assert_eq!(reread.image_step_for(&press(KeyCode::Char('B'), KeyModifiers::SHIFT)), Some(("brightness", 8)));
+        assert_eq!(reread.keys_for(Action::PanLeft), keys.keys_for(Action::PanLeft));
+
+        // Keys used by an action can't also step a control
+        let error = toml::from_str::<KeyBindings>("image.brightness.decrease = \"b\"").unwrap_err().to_string();
+        assert!(error.contains("already bound to Bookmark"), "{}", error);
+    }
+}
@@ -0,0 +1,30 @@
+// src/lib.rs
+//! Camera control, configuration and terminal UI for tiny-ptz. The binary in
+//! `main.rs` wires these together; `camera` can also be used on its own to drive
+//! a V4L2 PTZ camera from other programs. The terminal UI (`app`, `ui`, `keys` and what
+//! only it uses) needs the default `tui` feature.
+
+#[cfg(feature = "tui")]
+pub mod app;
+pub mod backend;
+pub mod camera;
+pub mod cli;
+pub mod detect;
+#[cfg(feature = "tui")]
+pub mod events;
+#[cfg(any(test, feature = "testing"))]
+pub mod fake;
+pub mod headless;
+#[cfg(feature = "tui")]
+pub mod keys;
+pub mod looks;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "tui")]
+pub mod picker;
+pub mod presets;
+pub mod state;
+#[cfg(feature = "tui")]
+pub mod theme;
+#[cfg(feature = "tui")]
+pub mod ui;
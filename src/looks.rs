@@ -0,0 +1,86 @@
+// src/looks.rs
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::Path;
+
+/// Directory of look files, next to `config.toml`: `looks/<name>.toml`
+pub const LOOKS_DIR: &str = "looks";
+
+/// A named set of image-control values, e.g. `looks/warm.toml` containing
+/// `brightness = 140` and `white_balance_temperature = 3200`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Look {
+    pub name: String,
+    pub controls: Vec<(String, i32)>,
+}
+
+impl Look {
+    /// `brightness=140, contrast=32`, for the status panel
+    pub fn summary(&self) -> String {
+        self.controls.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Names of the looks in `dir`, sorted. A missing directory has none.
+pub fn list_looks(dir: &Path) -> Result<Vec<String>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        })
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Reads `dir/<name>.toml`, a flat table of control names to values, keeping the file's
+/// order so that e.g. `white_balance_automatic = 0` goes out before the temperature it unlocks
+pub fn load_look(dir: &Path, name: &str) -> Result<Look> {
+    let path = dir.join(format!("{}.toml", name));
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let document: toml_edit::DocumentMut = contents.parse()
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let controls = document
+        .iter()
+        .map(|(control, item)| match item.as_integer().and_then(|value| i32::try_from(value).ok()) {
+            Some(value) => Ok((control.to_string(), value)),
+            None => bail!("Failed to parse {}: {} must be a whole number", path.display(), control),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if controls.is_empty() {
+        bail!("{} sets no controls", path.display());
+    }
+    Ok(Look { name: name.to_string(), controls })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_and_loads_looks() {
+        let dir = std::env::temp_dir().join(format!("tiny-ptz-looks-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("warm.toml"), "white_balance_automatic = 0\nwhite_balance_temperature = 3200\nbrightness = 140\n").unwrap();
+        fs::write(dir.join("cool.toml"), "white_balance_temperature = 6500\n").unwrap();
+        fs::write(dir.join("notes.txt"), "not a look").unwrap();
+
+        assert_eq!(list_looks(&dir).unwrap(), ["cool", "warm"]);
+        let warm = load_look(&dir, "warm").unwrap();
+        // In file order, not sorted
+        assert_eq!(warm.summary(), "white_balance_automatic=0, white_balance_temperature=3200, brightness=140");
+        fs::write(dir.join("bad.toml"), "brightness = \"high\"\n").unwrap();
+        assert!(load_look(&dir, "bad").unwrap_err().to_string().contains("brightness must be a whole number"));
+        assert!(load_look(&dir, "missing").is_err());
+        assert!(list_looks(&dir.join("nope")).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,50 +1,215 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+#[cfg(feature = "tui")]
 use crossterm::{
-    event::{self, Event as CrosstermEvent},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+#[cfg(feature = "tui")]
 use ratatui::backend::CrosstermBackend; // Import CrosstermBackend here
-use ratatui::Terminal; // Import Terminal separately for clarity
-use std::{io, time::Duration};
+#[cfg(feature = "tui")]
+use ratatui::{Terminal, TerminalOptions, Viewport}; // Import Terminal separately for clarity
+use std::path::{Path, PathBuf};
+#[cfg(feature = "tui")]
+use std::{io, time::{Duration, Instant}};
+#[cfg(feature = "tui")]
 use tokio::sync::mpsc;
 
-use crate::app::{App, InputEvent};
-use crate::camera::CameraConfig;
+#[cfg(feature = "tui")]
+use tiny_ptz::app::{App, InputEvent};
+use tiny_ptz::backend::V4l2Ctl;
+use tiny_ptz::camera::CameraConfig;
+use tiny_ptz::cli::{Args, Subcommand, USAGE};
+#[cfg(feature = "tui")]
+use tiny_ptz::events::{self, EventWatcher};
+use tiny_ptz::headless;
+#[cfg(feature = "tui")]
+use tiny_ptz::headless::wait_for_shutdown_signal;
+#[cfg(feature = "tui")]
+use tiny_ptz::picker::{self, Pick};
+use tiny_ptz::detect;
+#[cfg(feature = "tui")]
+use tiny_ptz::ui;
 
-mod app;
-mod camera;
-mod ui;
+/// Rows reserved for the dashboard when drawing without the alternate screen
+#[cfg(feature = "tui")]
+const INLINE_HEIGHT: u16 = 20;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = Args::parse()?;
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
     // Load configuration
-    let config_str = std::fs::read_to_string("config.toml")
-        .expect("Failed to read config.toml");
-    let config: CameraConfig = toml::from_str(&config_str)
-        .expect("Failed to parse config.toml");
+    let config_path = args.config.clone().unwrap_or_else(|| PathBuf::from("config.toml"));
+    let (mut config, config_str, mut warnings) = if args.lenient {
+        CameraConfig::load_lenient(&config_path)?
+    } else {
+        let (config, config_str) = CameraConfig::load(&config_path)?;
+        (config, config_str, Vec::new())
+    };
+    if let Some(profile) = args.profile.clone() {
+        config.motion_profile = Some(profile);
+    }
+    for warning in &warnings {
+        eprintln!("warning: {}", warning);
+    }
+
+    #[cfg(feature = "tui")]
+    config.release_hooked_keys();
+
+    // Both are for inspecting or fixing a config, so they run before it's validated
+    if args.print_config {
+        if args.detect {
+            let ranges = detect::query_ranges(&V4l2Ctl, &config.device)?;
+            detect::apply_ranges(&mut config, &ranges);
+        }
+        print!("{}", toml::to_string(&config).context("Failed to serialize the config")?);
+        return Ok(());
+    }
+
+    if args.detect {
+        let ranges = detect::query_ranges(&V4l2Ctl, &config.device)?;
+        print!("{}", detect::render_config(&config.device, &ranges, &config));
+        return Ok(());
+    }
+
+    match config.validate() {
+        Err(e) if args.lenient => {
+            // Only --profile can break a config parse_lenient has already checked
+            let warning = format!("--profile: {:#}; starting without a motion profile", e);
+            eprintln!("warning: {}", warning);
+            warnings.push(warning);
+            config.motion_profile = None;
+        }
+        result => result?,
+    }
+
+    if let Some(command) = args.command {
+        return match command {
+            Subcommand::Track => headless::run_track(config).await,
+            command => headless::run_subcommand(config, command),
+        };
+    }
+
+    if args.oneline {
+        return headless::run_oneline(config).await;
+    }
+
+    run_tui(args, config, &config_path, &config_str, &warnings).await
+}
+
+#[cfg(not(feature = "tui"))]
+async fn run_tui(_args: Args, _config: CameraConfig, _config_path: &Path, _config_str: &str, _warnings: &[String]) -> Result<()> {
+    bail!("This tiny-ptz was built without the `tui` feature; use a subcommand, --oneline or --detect (see --help)")
+}
+
+#[cfg(feature = "tui")]
+async fn run_tui(args: Args, mut config: CameraConfig, config_path: &Path, config_str: &str, warnings: &[String]) -> Result<()> {
+    // Offer a picker when asked to, or when the configured device doesn't exist
+    let devices = if args.pick_device || !Path::new(&config.device).exists() {
+        detect::list_devices(&V4l2Ctl).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    if args.pick_device && devices.is_empty() {
+        bail!("--pick-device: v4l2-ctl --list-devices found no cameras");
+    }
+
+    #[cfg(feature = "metrics")]
+    let metrics = match &config.metrics {
+        Some(settings) => {
+            let listener = tokio::net::TcpListener::bind(&settings.listen)
+                .await
+                .with_context(|| format!("[metrics] could not listen on {}", settings.listen))?;
+            let metrics = std::sync::Arc::new(tiny_ptz::metrics::Metrics::default());
+            tokio::spawn(tiny_ptz::metrics::serve(listener, metrics.clone()));
+            Some(metrics)
+        }
+        None => None,
+    };
+    #[cfg(not(feature = "metrics"))]
+    if config.metrics.is_some() {
+        bail!("[metrics] needs tiny-ptz built with `--features metrics`");
+    }
 
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    let viewport = if args.no_altscreen {
+        // Draw in a fixed-height region below the cursor so output stays in scrollback
+        Viewport::Inline(INLINE_HEIGHT)
+    } else {
+        execute!(stdout, EnterAlternateScreen)?;
+        Viewport::Fullscreen
+    };
+    let mouse = config.ui.mouse;
+    if mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?; // `Terminal` should now be resolved
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?; // `Terminal` should now be resolved
+
+    if !devices.is_empty() {
+        match picker::pick_device(&mut terminal, &devices, &config.device, &config.ui.theme)? {
+            // A config piped on stdin has no file to write back to
+            Pick::Save(device) if config_path != Path::new("-") => {
+                std::fs::write(config_path, picker::with_device(config_str, &device))?;
+                config.device = device;
+            }
+            Pick::Session(device) | Pick::Save(device) => config.device = device,
+            Pick::Cancel => {}
+        }
+        terminal.clear()?;
+    }
 
     // Create app and run it
     let mut app = App::new(config);
+    app.verbose = args.verbose;
+    app.plain = args.plain;
+    // --lenient: the warnings printed before the TUI started would be hidden behind it
+    if !warnings.is_empty() {
+        app.status_message = format!("Config defaults substituted: {}", warnings.join(" | "));
+    }
+    // A config piped on stdin has no file to write [presets] back to, so they go to presets.toml
+    if config_path != Path::new("-") {
+        app.use_config_presets(config_path);
+    }
+
+    // JSON events for external tools: positions straight from the controller, status and
+    // mode changes from the loop below
+    let mut events = args.events.clone().map(|path| {
+        let (events_tx, events_rx) = mpsc::channel(256);
+        let writer = tokio::spawn(events::write_events(path, events_rx));
+        tokio::spawn(events::forward_positions(app.camera_controller.subscribe(), events_tx.clone()));
+        (events_tx, writer)
+    });
+    let mut event_watcher = EventWatcher::default();
 
     // Event handling channel
     let (tx, mut rx) = mpsc::channel(100);
 
+    // Signal task: Ctrl+C from outside raw mode (or `kill`) quits like 'q' so cleanup still runs
+    let signal_tx = tx.clone();
+    tokio::spawn(async move {
+        if wait_for_shutdown_signal().await.is_ok() {
+            let _ = signal_tx.send(InputEvent::Shutdown).await;
+        }
+    });
+
     // Input polling task
     tokio::spawn(async move {
         loop {
             // Poll for events. Adjust poll duration as needed.
             if event::poll(Duration::from_millis(50)).unwrap() {
-                if let CrosstermEvent::Key(key) = event::read().unwrap() {
-                    // Send key events to the app
-                    tx.send(InputEvent::Key(key)).await.unwrap();
+                // Send key and mouse events to the app
+                match event::read().unwrap() {
+                    CrosstermEvent::Key(key) => tx.send(InputEvent::Key(key)).await.unwrap(),
+                    CrosstermEvent::Mouse(mouse) => tx.send(InputEvent::Mouse(mouse)).await.unwrap(),
+                    _ => {}
                 }
             }
             // Send a tick event regularly to update UI or handle time-based logic
@@ -54,13 +219,50 @@ async fn main() -> Result<()> {
         }
     });
 
+    // Updates that arrive faster than `ui.max_fps` are folded into the next frame
+    let mut frames = ui::FrameLimiter::new(app.camera_controller.config.ui.max_fps);
+    let mut dirty = true;
     loop {
         // Draw the UI
-        terminal.draw(|f| ui::render::<CrosstermBackend<io::Stdout>>(f, &app))?;
+        if dirty && frames.wait(Instant::now()).is_zero() {
+            terminal.draw(|f| ui::render(f, &app))?;
+            frames.drawn(Instant::now());
+            dirty = false;
+        }
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &metrics {
+            metrics.observe(&app.camera_controller);
+        }
+        // The writer only stops on an error, which would otherwise go unseen
+        if events.as_ref().is_some_and(|(_, writer)| writer.is_finished()) {
+            if let Some((_, writer)) = events.take() {
+                match writer.await {
+                    Ok(Ok(())) => app.status_message = "--events: the event writer stopped.".to_string(),
+                    Ok(Err(e)) => app.set_error(format!("--events stopped: {:#}", e)),
+                    Err(e) => app.set_error(format!("--events stopped: {}", e)),
+                }
+                dirty = true;
+            }
+        }
+        if let Some((events_tx, _)) = &events {
+            // Drop events rather than stall the UI if the reader falls behind
+            for event in event_watcher.changes(&app) {
+                let _ = events_tx.try_send(event);
+            }
+        }
 
-        // Process events from the channel
-        if let Some(event) = rx.recv().await {
+        // Process events from the channel, waking for a deferred frame if one is due first
+        let event = if dirty {
+            match tokio::time::timeout(frames.wait(Instant::now()), rx.recv()).await {
+                Ok(event) => event,
+                Err(_) => continue,
+            }
+        } else {
+            rx.recv().await
+        };
+        if let Some(event) = event {
             app.update(event);
+            dirty = true;
             if app.should_quit {
                 break;
             }
@@ -69,11 +271,23 @@ async fn main() -> Result<()> {
 
     // Restore terminal state before exiting
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    if mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    if !args.no_altscreen {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    }
     terminal.show_cursor()?;
 
-    // Cleanup any running processes
-    app.cleanup();
+    // Write the session log first so a failed save still leaves a record
+    let logged = match &args.session_log {
+        Some(path) => app.write_session_log(path, config_path),
+        None => Ok(()),
+    };
+
+    // Cleanup any running processes and save the session, unless quit with 'Q'
+    app.cleanup()?;
+    logged?;
 
     Ok(())
-}
\ No newline at end of file
+}
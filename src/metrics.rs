@@ -0,0 +1,107 @@
+// src/metrics.rs
+//! Prometheus `/metrics` endpoint for the `metrics` feature: the current position and
+//! counts of `v4l2-ctl` calls, for monitoring installed cameras.
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::camera::{Axis, CameraController, CommandCounts, Position};
+
+/// The latest values, copied out of the controller by the UI loop and read by the server
+#[derive(Debug, Default)]
+pub struct Metrics {
+    snapshot: Mutex<Option<(Position, CommandCounts)>>,
+}
+
+impl Metrics {
+    /// Takes the controller's current position and command counts
+    pub fn observe(&self, controller: &CameraController) {
+        *self.snapshot.lock().unwrap() = Some((controller.position(), controller.command_counts()));
+    }
+
+    /// The metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let Some((position, counts)) = *self.snapshot.lock().unwrap() else {
+            return String::new();
+        };
+        let mut out = String::new();
+        let _ = writeln!(out, "# HELP tiny_ptz_position Current control value of each axis");
+        let _ = writeln!(out, "# TYPE tiny_ptz_position gauge");
+        for axis in Axis::ALL {
+            let value = match axis {
+                Axis::Pan => position.pan,
+                Axis::Tilt => position.tilt,
+                Axis::Zoom => position.zoom,
+            };
+            let _ = writeln!(out, "tiny_ptz_position{{axis=\"{}\"}} {}", axis.name(), value);
+        }
+        let _ = writeln!(out, "# HELP tiny_ptz_commands_total v4l2-ctl calls made");
+        let _ = writeln!(out, "# TYPE tiny_ptz_commands_total counter");
+        let _ = writeln!(out, "tiny_ptz_commands_total {}", counts.sent);
+        let _ = writeln!(out, "# HELP tiny_ptz_command_failures_total v4l2-ctl calls that failed or couldn't be started");
+        let _ = writeln!(out, "# TYPE tiny_ptz_command_failures_total counter");
+        let _ = writeln!(out, "tiny_ptz_command_failures_total {}", counts.failed);
+        out
+    }
+}
+
+/// Answers `GET /metrics` on `listener` until the program exits; other paths get a 404
+pub async fn serve(listener: TcpListener, metrics: Arc<Metrics>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            let _ = respond(stream, &metrics).await;
+        });
+    }
+}
+
+async fn respond(mut stream: TcpStream, metrics: &Metrics) -> std::io::Result<()> {
+    // The request line is all that matters, and it fits in the first read
+    let mut request = [0; 1024];
+    let read = stream.read(&mut request).await?;
+    let request_line = String::from_utf8_lossy(&request[..read]);
+    let (status, body) = match request_line.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+        ["GET", "/metrics"] => ("200 OK", metrics.render()),
+        _ => ("404 Not Found", "Not found; try /metrics\n".to_string()),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::CameraConfig;
+    use crate::fake::FakeCamera;
+
+    #[tokio::test]
+    async fn serves_position_and_command_counts() {
+        let camera = FakeCamera::new().without_control("zoom_absolute");
+        let mut controller = CameraController::with_executor(CameraConfig::default(), Arc::new(camera));
+        controller.set_pan(1).unwrap();
+        let _ = controller.read_values(&["zoom_absolute"]);
+        let metrics = Arc::new(Metrics::default());
+        metrics.observe(&controller);
+        let pan = format!("tiny_ptz_position{{axis=\"pan\"}} {}\n", controller.get_pan());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, metrics));
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.contains(&pan), "{}", response);
+        assert!(response.contains("tiny_ptz_commands_total 2\n"), "{}", response);
+        assert!(response.contains("tiny_ptz_command_failures_total 1\n"), "{}", response);
+    }
+}
@@ -0,0 +1,103 @@
+// src/picker.rs
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Terminal,
+};
+
+use crate::detect::VideoDevice;
+use crate::theme::Theme;
+
+/// What was chosen in the device picker
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pick {
+    /// Use this device for the current session only
+    Session(String),
+    /// Use this device and write it back to `config.toml`
+    Save(String),
+    /// Keep the configured device
+    Cancel,
+}
+
+/// Shows the detected cameras in a list until one is chosen with Enter (or `w` to also save it)
+pub fn pick_device<B: Backend>(terminal: &mut Terminal<B>, devices: &[VideoDevice], current: &str, theme: &Theme) -> Result<Pick> {
+    let mut state = ListState::default();
+    state.select(Some(devices.iter().position(|d| d.node == current).unwrap_or(0)));
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(3)])
+                .split(f.size());
+            let items: Vec<ListItem> = devices
+                .iter()
+                .map(|device| ListItem::new(format!("{}  {}", device.node, device.name)))
+                .collect();
+            f.render_stateful_widget(
+                List::new(items)
+                    .block(Block::default().borders(Borders::ALL).title("Select a camera"))
+                    .highlight_style(Style::default().fg(theme.title).add_modifier(Modifier::BOLD))
+                    .highlight_symbol("> "),
+                chunks[0],
+                &mut state,
+            );
+            f.render_widget(
+                Paragraph::new(format!(
+                    "↑/↓: select  Enter: use  w: use and save to config.toml  q/Esc: keep {}",
+                    current
+                ))
+                .block(Block::default().borders(Borders::ALL).title("Help")),
+                chunks[1],
+            );
+        })?;
+
+        if let Event::Key(key) = event::read()? {
+            let selected = state.selected().unwrap_or(0);
+            match key.code {
+                KeyCode::Up => state.select(Some(selected.saturating_sub(1))),
+                KeyCode::Down => state.select(Some((selected + 1).min(devices.len().saturating_sub(1)))),
+                KeyCode::Enter => return Ok(Pick::Session(devices[selected].node.clone())),
+                KeyCode::Char('w') => return Ok(Pick::Save(devices[selected].node.clone())),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(Pick::Cancel),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Rewrites the top-level `device = ...` line of a `config.toml`, leaving everything
+/// else (comments, sections) as it was. Adds the line at the top if there is none.
+pub fn with_device(config_text: &str, device: &str) -> String {
+    let new_line = format!("device = \"{}\"", device.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut lines: Vec<String> = config_text.lines().map(str::to_string).collect();
+    let top_level_end = lines.iter().position(|line| line.trim_start().starts_with('[')).unwrap_or(lines.len());
+    let existing = lines[..top_level_end].iter().position(|line| {
+        line.split_once('=').is_some_and(|(key, _)| key.trim() == "device")
+    });
+    match existing {
+        Some(index) => lines[index] = new_line,
+        None => lines.insert(0, new_line),
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_only_the_top_level_device() {
+        let config = "# my camera\ndevice = \"/dev/video0\"\n\n[video]\ndevice = \"ignored\"\n";
+        assert_eq!(
+            with_device(config, "/dev/video2"),
+            "# my camera\ndevice = \"/dev/video2\"\n\n[video]\ndevice = \"ignored\"\n"
+        );
+        assert_eq!(with_device("[pan]\nstep = 1\n", "/dev/video1"), "device = \"/dev/video1\"\n[pan]\nstep = 1\n");
+    }
+}
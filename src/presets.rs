@@ -0,0 +1,258 @@
+// src/presets.rs
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// File the bookmark ring is persisted to, next to `config.toml`
+pub const PRESETS_FILE: &str = "presets.toml";
+
+/// Shared, read-only presets for every user of the machine, listed before the user's own
+pub const SYSTEM_PRESETS_FILE: &str = "/etc/tiny-ptz/presets.toml";
+
+/// A saved pan/tilt/zoom position, optionally named as a framing (e.g. "wide desk")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub pan: i32,
+    pub tilt: i32,
+    pub zoom: i32,
+    /// Shown in the status panel when the preset is recalled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Output aspect ratio the framing was composed for, e.g. "16:9" or "9:16".
+    /// Advisory only: it is kept and displayed but doesn't affect movement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aspect: Option<String>,
+    /// Loaded from the system file: recallable, but never written back
+    #[serde(skip)]
+    pub system: bool,
+}
+
+impl Preset {
+    /// An unlabeled preset at the given position
+    pub fn at(pan: i32, tilt: i32, zoom: i32) -> Self {
+        Preset { pan, tilt, zoom, label: None, aspect: None, system: false }
+    }
+
+    /// The label and aspect for display, e.g. "vertical portrait (9:16)"
+    pub fn description(&self) -> Option<String> {
+        match (&self.label, &self.aspect) {
+            (Some(label), Some(aspect)) => Some(format!("{} ({})", label, aspect)),
+            (Some(label), None) => Some(label.clone()),
+            (None, Some(aspect)) => Some(format!("({})", aspect)),
+            (None, None) => None,
+        }
+    }
+
+    /// "system" or "personal", for the status panel
+    pub fn source(&self) -> &'static str {
+        if self.system { "system" } else { "personal" }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PresetsFile {
+    #[serde(default)]
+    bookmark: Vec<Preset>,
+}
+
+/// Loads the bookmark ring, treating a missing file as an empty ring
+pub fn load_bookmarks(path: &Path) -> Result<Vec<Preset>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let file: PresetsFile = toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(file.bookmark)
+}
+
+/// Loads the system presets followed by the user's. A user preset with the same label
+/// as a system one replaces it; either file may be missing.
+pub fn load_all(system_path: &Path, user_path: &Path) -> Result<Vec<Preset>> {
+    Ok(with_system_presets(load_bookmarks(system_path)?, load_bookmarks(user_path)?))
+}
+
+/// Puts the `system` presets, marked as such, in front of `user`, as `load_all` does
+pub fn with_system_presets(system: Vec<Preset>, user: Vec<Preset>) -> Vec<Preset> {
+    let mut presets: Vec<Preset> = system
+        .into_iter()
+        .filter(|system| {
+            system.label.is_none() || !user.iter().any(|preset| preset.label == system.label)
+        })
+        .map(|preset| Preset { system: true, ..preset })
+        .collect();
+    presets.extend(user);
+    presets
+}
+
+/// Writes the personal bookmarks; system presets in the ring are left out
+pub fn save_bookmarks(path: &Path, bookmarks: &[Preset]) -> Result<()> {
+    let file = PresetsFile { bookmark: bookmarks.iter().filter(|b| !b.system).cloned().collect() };
+    fs::write(path, toml::to_string(&file)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Where the personal bookmarks are written back to
+#[derive(Debug, Clone, PartialEq)]
+pub enum PresetsStore {
+    /// A presets file of `[[bookmark]]` entries, normally `PRESETS_FILE`
+    File(PathBuf),
+    /// The `[presets]` section of this config file
+    Config(PathBuf),
+}
+
+impl PresetsStore {
+    pub fn save(&self, bookmarks: &[Preset]) -> Result<()> {
+        match self {
+            PresetsStore::File(path) => save_bookmarks(path, bookmarks),
+            PresetsStore::Config(path) => {
+                // Re-read so changes made since startup (e.g. a picked device) are kept
+                let text = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+                // Slots written here would be merged with the included ones, bringing deleted presets back
+                if crate::camera::includes_set(&text, path, "presets")? {
+                    bail!("[presets] in {} comes from an included file; move it into {} to save presets there", path.display(), path.display());
+                }
+                let text = with_presets(&text, bookmarks).with_context(|| format!("Failed to save presets to {}", path.display()))?;
+                fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+            }
+        }
+    }
+}
+
+impl fmt::Display for PresetsStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetsStore::File(path) => write!(f, "{}", path.display()),
+            PresetsStore::Config(path) => write!(f, "[presets] in {}", path.display()),
+        }
+    }
+}
+
+/// Rewrites the `[presets]` section of a `config.toml` with the personal bookmarks as
+/// numbered slots. Slots that are kept keep their comments, and the rest of the file is left
+/// as it was. Presets written inline or with dotted keys are refused rather than duplicated.
+pub fn with_presets(config_text: &str, bookmarks: &[Preset]) -> Result<String> {
+    let mut document: toml_edit::DocumentMut = config_text.parse().context("Failed to parse the config")?;
+    let presets = match document.get_mut("presets") {
+        Some(toml_edit::Item::Table(table)) if !table.is_dotted() && !table.is_implicit() => table,
+        None => bail!("the config has no [presets] section"),
+        Some(_) => bail!("presets are written inline or with dotted keys; make them a plain [presets] section to save there"),
+    };
+    let personal: Vec<&Preset> = bookmarks.iter().filter(|b| !b.system).collect();
+    let stale: Vec<String> = presets
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| key.parse::<usize>().map_or(true, |slot| slot == 0 || slot > personal.len()))
+        .collect();
+    for key in stale {
+        presets.remove(&key);
+    }
+    for (slot, preset) in personal.into_iter().enumerate() {
+        let mut fields = toml_edit::InlineTable::new();
+        fields.insert("pan", i64::from(preset.pan).into());
+        fields.insert("tilt", i64::from(preset.tilt).into());
+        fields.insert("zoom", i64::from(preset.zoom).into());
+        for (key, value) in [("label", &preset.label), ("aspect", &preset.aspect)] {
+            if let Some(value) = value {
+                fields.insert(key, value.as_str().into());
+            }
+        }
+        let mut entry = toml_edit::Value::InlineTable(fields);
+        let key = (slot + 1).to_string();
+        match presets.get_mut(&key) {
+            // Assigned in place, so comments above and after the slot's line stay
+            Some(item) => {
+                if let Some(old) = item.as_value() {
+                    *entry.decor_mut() = old.decor().clone();
+                }
+                *item = toml_edit::Item::Value(entry);
+            }
+            None => {
+                presets.insert(&key, toml_edit::Item::Value(entry));
+            }
+        }
+    }
+    presets.sort_values_by(|a, _, b, _| a.parse::<usize>().ok().cmp(&b.parse::<usize>().ok()));
+    Ok(document.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, contents: &str) -> std::path::PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn user_presets_extend_and_override_system_ones() {
+        let dir = std::env::temp_dir().join(format!("tiny-ptz-presets-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let system = write(&dir, "system.toml", "\
+[[bookmark]]
+pan = 0
+tilt = 0
+zoom = 0
+label = \"wide\"
+
+[[bookmark]]
+pan = 100
+tilt = 0
+zoom = 50
+label = \"door\"
+");
+        let user = write(&dir, "user.toml", "[[bookmark]]\npan = 5\ntilt = 5\nzoom = 5\nlabel = \"door\"\n");
+
+        let presets = load_all(&system, &user).unwrap();
+        let summary: Vec<(Option<&str>, i32, &str)> =
+            presets.iter().map(|p| (p.label.as_deref(), p.pan, p.source())).collect();
+        assert_eq!(summary, [(Some("wide"), 0, "system"), (Some("door"), 5, "personal")]);
+
+        save_bookmarks(&user, &presets).unwrap();
+        assert_eq!(load_bookmarks(&user).unwrap().len(), 1);
+        assert_eq!(load_all(&dir.join("missing.toml"), &user).unwrap().len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn inline_presets_are_rewritten_in_place_of_the_old_section() {
+        let config = "device = \"/dev/video2\"\n\n[presets]\n# by the door\n2 = { pan = 9, tilt = 9, zoom = 9 } # old\n3 = { pan = 8, tilt = 8, zoom = 8 }\n\n[pan]\nstep = 100\n";
+        let bookmarks = [
+            Preset { label: Some("say \"hi\"".to_string()), ..Preset::at(1, 2, 3) },
+            Preset { system: true, ..Preset::at(7, 7, 7) },
+            Preset::at(4, 5, 6),
+        ];
+        let text = with_presets(config, &bookmarks).unwrap();
+        assert_eq!(
+            text,
+            "device = \"/dev/video2\"\n\n[presets]\n1 = { pan = 1, tilt = 2, zoom = 3, label = 'say \"hi\"' }\n\
+             # by the door\n2 = { pan = 4, tilt = 5, zoom = 6 } # old\n\n[pan]\nstep = 100\n"
+        );
+        let reloaded = crate::camera::CameraConfig::parse(&text, "config", None).unwrap();
+        assert_eq!(reloaded.pan.step, 100);
+        assert_eq!(reloaded.inline_presets().unwrap(), [bookmarks[0].clone(), bookmarks[2].clone()]);
+
+        // Anything but a plain [presets] section is left alone
+        for config in ["presets = { 1 = { pan = 0, tilt = 0, zoom = 0 } }\n", "presets.1 = { pan = 0, tilt = 0, zoom = 0 }\n", "device = \"/dev/video2\"\n"] {
+            assert!(with_presets(config, &bookmarks).is_err(), "{}", config);
+        }
+    }
+
+    #[test]
+    fn presets_from_an_include_are_not_saved_over() {
+        let dir = std::env::temp_dir().join(format!("tiny-ptz-presets-include-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        write(&dir, "shared.toml", "[presets]\n1 = { pan = 1, tilt = 1, zoom = 1 }\n");
+        let config = write(&dir, "config.toml", "include = [\"shared.toml\"]\n\n[presets]\n2 = { pan = 2, tilt = 2, zoom = 2 }\n");
+
+        let error = PresetsStore::Config(config.clone()).save(&[Preset::at(3, 3, 3)]).unwrap_err();
+        assert!(error.to_string().contains("included file"), "{}", error);
+        assert!(fs::read_to_string(&config).unwrap().contains("2 = { pan = 2"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
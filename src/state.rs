@@ -0,0 +1,39 @@
+// src/state.rs
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// File UI preferences changed at runtime are persisted to, next to `presets.toml`
+pub const STATE_FILE: &str = "state.toml";
+
+/// Preferences toggled from the keyboard that should survive a restart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    pub show_speed_info: bool,
+    /// Name of the built-in theme last selected with the theme key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State { show_speed_info: true, theme: None }
+    }
+}
+
+/// Loads the saved state, treating a missing file as the defaults
+pub fn load_state(path: &Path) -> Result<State> {
+    if !path.exists() {
+        return Ok(State::default());
+    }
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+pub fn save_state(path: &Path, state: &State) -> Result<()> {
+    fs::write(path, toml::to_string(state)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
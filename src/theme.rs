@@ -0,0 +1,72 @@
+// src/theme.rs
+use std::str::FromStr;
+use ratatui::style::Color;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Panel colours, set in the `[ui.theme]` section. Colours are names (`"magenta"`,
+/// `"lightblue"`), 256-colour indexes (`"141"`) or hex (`"#ff8800"`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    #[serde(deserialize_with = "color", serialize_with = "color_name")]
+    pub title: Color,
+    #[serde(deserialize_with = "color", serialize_with = "color_name")]
+    pub gauge_fg: Color,
+    #[serde(deserialize_with = "color", serialize_with = "color_name")]
+    pub gauge_bg: Color,
+    #[serde(deserialize_with = "color", serialize_with = "color_name")]
+    pub speed_info: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            title: Color::Cyan,
+            gauge_fg: Color::Magenta,
+            gauge_bg: Color::Black,
+            speed_info: Color::Yellow,
+        }
+    }
+}
+
+/// Built-in themes, in the order the theme key cycles through them.
+/// "default" is whatever `[ui.theme]` configures (the colours above if it's absent).
+pub const THEME_NAMES: [&str; 4] = ["default", "high-contrast", "monochrome", "solarized"];
+
+impl Theme {
+    /// A built-in theme by name, with `configured` standing in for "default"
+    pub fn named(name: &str, configured: &Theme) -> Option<Theme> {
+        let theme = match name {
+            "default" => configured.clone(),
+            "high-contrast" => Theme {
+                title: Color::White,
+                gauge_fg: Color::Yellow,
+                gauge_bg: Color::Black,
+                speed_info: Color::White,
+            },
+            "monochrome" => Theme {
+                title: Color::Reset,
+                gauge_fg: Color::White,
+                gauge_bg: Color::Black,
+                speed_info: Color::Reset,
+            },
+            "solarized" => Theme {
+                title: Color::Rgb(0x2a, 0xa1, 0x98),    // cyan
+                gauge_fg: Color::Rgb(0x26, 0x8b, 0xd2), // blue
+                gauge_bg: Color::Rgb(0x07, 0x36, 0x42), // base02
+                speed_info: Color::Rgb(0xb5, 0x89, 0x00), // yellow
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+}
+
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    Color::from_str(&name).map_err(|_| serde::de::Error::custom(format!("unknown colour '{}'", name)))
+}
+
+fn color_name<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&color.to_string())
+}
@@ -1,116 +1,863 @@
+use crate::app::App;
+use crate::camera::{Axis, ControlConfig, SLOW_COMMAND_THRESHOLD};
+use crate::theme::Theme;
 use ratatui::{
-    backend::Backend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Gauge, Paragraph},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Gauge, LineGauge, Paragraph, Wrap},
     Frame,
 };
-use crate::app::App;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Display settings from the `[ui]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    /// Follow the zoom percentage with the raw `zoom_absolute` value, e.g. "73% (50)"
+    pub show_raw_zoom: bool,
+    pub theme: Theme,
+    pub layout: PanelLayout,
+    /// Capture the mouse so the scroll wheel moves the panel under the cursor. Off by
+    /// default, which keeps the terminal's own text selection.
+    pub mouse: bool,
+    /// Most redraws per second; updates arriving faster are folded into the next frame.
+    /// 0 redraws after every update.
+    pub max_fps: u32,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        UiConfig {
+            show_raw_zoom: false,
+            theme: Theme::default(),
+            layout: PanelLayout::default(),
+            mouse: false,
+            max_fps: 60,
+        }
+    }
+}
+
+/// Readout settings from the `[display]` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    /// Decimal places in pan/tilt degree readouts, clamped to 0–3
+    pub degree_precision: u8,
+    /// Show the side of centre as a letter (`12.5° R`, `3.0° D`) instead of a sign
+    pub degree_direction: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        DisplayConfig {
+            degree_precision: 1,
+            degree_direction: false,
+        }
+    }
+}
+
+/// How the PTZ panels and the Help panel share the screen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PanelLayout {
+    /// Side by side
+    #[default]
+    Horizontal,
+    /// PTZ panels above Help, for tall or narrow terminals
+    Vertical,
+    /// Vertical when the terminal is less than twice as wide as it is tall
+    /// (terminal cells are roughly twice as tall as they are wide), otherwise horizontal
+    Auto,
+}
+
+impl PanelLayout {
+    /// The split direction for an area of this size
+    pub fn direction(self, area: Rect) -> Direction {
+        match self {
+            PanelLayout::Horizontal => Direction::Horizontal,
+            PanelLayout::Vertical => Direction::Vertical,
+            PanelLayout::Auto if area.width < area.height.saturating_mul(2) => Direction::Vertical,
+            PanelLayout::Auto => Direction::Horizontal,
+        }
+    }
+}
+
+/// Caps how often the screen is redrawn at `ui.max_fps`
+#[derive(Debug, Clone)]
+pub struct FrameLimiter {
+    interval: Duration,
+    last_draw: Option<Instant>,
+}
+
+impl FrameLimiter {
+    pub fn new(max_fps: u32) -> Self {
+        let interval = if max_fps == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs(1) / max_fps
+        };
+        FrameLimiter {
+            interval,
+            last_draw: None,
+        }
+    }
+
+    /// How long from `now` until the next frame may be drawn; zero if it may be drawn now
+    pub fn wait(&self, now: Instant) -> Duration {
+        self.last_draw.map_or(Duration::ZERO, |last| {
+            (last + self.interval).saturating_duration_since(now)
+        })
+    }
+
+    /// Records a frame drawn at `now`
+    pub fn drawn(&mut self, now: Instant) {
+        self.last_draw = Some(now);
+    }
+}
+
+/// Countdown label for the watchdog, rounded up to the second: "home in 42s", "home in 2:05"
+pub fn watchdog_label(remaining: Duration) -> String {
+    let secs = remaining.as_millis().div_ceil(1000);
+    match secs {
+        0..=59 => format!("home in {}s", secs),
+        _ => format!("home in {}:{:02}", secs / 60, secs % 60),
+    }
+}
+
+/// Zoom gauge label: the `[zoom.display]` multiplier if there is one, otherwise the
+/// percentage shown by the bar, plus the raw value if configured
+pub fn zoom_label(percent: f64, raw: i32, ui: &UiConfig, zoom: &ControlConfig) -> String {
+    let reading = match zoom
+        .display
+        .as_ref()
+        .and_then(|display| display.multiplier(raw))
+    {
+        Some(multiplier) => format!("{:.1}x", multiplier),
+        None => format!("{:.0}%", percent),
+    };
+    if ui.show_raw_zoom {
+        format!("{} ({})", reading, raw)
+    } else {
+        reading
+    }
+}
+
+/// Pan/tilt position in degrees, rounded to `degree_precision`, and with
+/// `degree_direction` the side of centre ("left"/"right" for pan, "down"/"up" for tilt,
+/// matching the motion arrows) in place of the sign. `None` without `units_per_degree`.
+fn degree_reading(
+    axis: Axis,
+    value: i32,
+    control: &ControlConfig,
+    display: &DisplayConfig,
+) -> Option<(String, Option<&'static str>)> {
+    let degrees = value as f64 / control.units_per_degree?;
+    let precision = display.degree_precision.min(3) as usize;
+    if !display.degree_direction {
+        return Some((format!("{:.*}", precision, degrees), None));
+    }
+    let magnitude = format!("{:.*}", precision, degrees.abs());
+    let (falling, rising) = if axis == Axis::Tilt {
+        ("down", "up")
+    } else {
+        ("left", "right")
+    };
+    // Values that round to zero are on centre, with no side to name
+    let side = match magnitude.trim_start_matches(['0', '.']).is_empty() {
+        true => None,
+        false if degrees < 0.0 => Some(falling),
+        false => Some(rising),
+    };
+    Some((magnitude, side))
+}
 
-pub fn render<B: Backend>(f: &mut Frame, app: &App) { // Remove <B> from Frame
+/// Degree readout for the Pan and Tilt panels, e.g. "-12.5°" or "12.5° L"
+pub fn degree_label(
+    axis: Axis,
+    value: i32,
+    control: &ControlConfig,
+    display: &DisplayConfig,
+) -> Option<String> {
+    let (degrees, side) = degree_reading(axis, value, control, display)?;
+    Some(match side {
+        Some(side) => format!("{}° {}", degrees, side[..1].to_uppercase()),
+        None => format!("{}°", degrees),
+    })
+}
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.plain {
+        render_plain(f, app);
+        return;
+    }
+    let ui_config = &app.camera_controller.config.ui;
+    let theme = app.theme();
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Title and Status
-            Constraint::Min(0),    // Main content
+            Constraint::Length(3),                               // Title and Status
+            Constraint::Min(0),                                  // Main content
+            Constraint::Length(if app.verbose { 1 } else { 0 }), // Debug strip
         ])
         .split(f.size());
 
+    if app.verbose {
+        let latency = app.camera_controller.latency();
+        let ms = |d: std::time::Duration| format!("{:.1}ms", d.as_secs_f64() * 1000.0);
+        let mut strip = match (latency.last(), latency.average()) {
+            (Some(last), Some(average)) => format!(
+                "v4l2-ctl: last {}, avg {} over {} calls",
+                ms(last),
+                ms(average),
+                latency.samples()
+            ),
+            _ => "v4l2-ctl: no calls yet".to_string(),
+        };
+        if app.camera_controller.config.movement.adaptive_interval {
+            strip.push_str(&format!(
+                "  interval {} (adaptive)",
+                ms(app.command_interval())
+            ));
+        }
+        let mut style = Style::default().fg(theme.speed_info);
+        if latency.is_slow() {
+            strip.push_str(&format!("  SLOW (> {})", ms(SLOW_COMMAND_THRESHOLD)));
+            style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+        }
+        f.render_widget(Paragraph::new(strip).style(style), chunks[2]);
+    }
+
     // Title Block
+    let mut title = match app.motion_profile() {
+        Some(profile) => format!("Camera PTZ Controller  [profile: {}]", profile),
+        None => "Camera PTZ Controller".to_string(),
+    };
+    if app.arrows_zoom() {
+        title.push_str("  [arrows: zoom]");
+    }
+    if app.locked() {
+        title.push_str("  LOCKED 🔒");
+    }
     f.render_widget(
-        Paragraph::new("Camera PTZ Controller")
-            .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        Paragraph::new(title)
+            .style(
+                Style::default()
+                    .fg(theme.title)
+                    .add_modifier(Modifier::BOLD),
+            )
             .block(Block::default().borders(Borders::ALL).title("Info")),
         chunks[0],
     );
 
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(50),
-            Constraint::Percentage(50),
-        ])
-        .split(chunks[1]);
+    // Pan/Tilt Block; axes the camera doesn't support get no panel
+    let config = &app.camera_controller.config;
+    let axis_height = |control: &ControlConfig| if control.supported { 3 } else { 0 };
+    let panel_heights = [
+        axis_height(&config.pan),
+        axis_height(&config.tilt),
+        axis_height(&config.zoom),
+        if app.show_speed_info() { 5 } else { 0 },
+        if app.watchdog_countdown().is_some() {
+            1
+        } else {
+            0
+        },
+    ];
+
+    let main_chunks = match ui_config.layout.direction(chunks[1]) {
+        Direction::Horizontal => Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[1]),
+        // Stacked: the PTZ panels plus a few lines of status, with Help below
+        Direction::Vertical => Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(panel_heights.iter().sum::<u16>() + 4),
+                Constraint::Min(0),
+            ])
+            .split(chunks[1]),
+    };
 
-    // Pan/Tilt Block
     let ptz_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Pan
-            Constraint::Length(3), // Tilt
-            Constraint::Length(3), // Zoom
-            Constraint::Length(4), // Movement Speed Info
-            Constraint::Min(0),    // Status/Help
+            Constraint::Length(panel_heights[0]), // Pan
+            Constraint::Length(panel_heights[1]), // Tilt
+            Constraint::Length(panel_heights[2]), // Zoom
+            Constraint::Length(panel_heights[3]), // Movement Speed Info
+            Constraint::Length(panel_heights[4]), // Watchdog countdown
+            Constraint::Min(0),                   // Status/Help
         ])
         .split(main_chunks[0]);
+    let panel_area = |control: &ControlConfig, area: Rect| {
+        if control.supported {
+            area
+        } else {
+            Rect::default()
+        }
+    };
+    app.set_panel_areas([
+        panel_area(&config.pan, ptz_chunks[0]),
+        panel_area(&config.tilt, ptz_chunks[1]),
+        panel_area(&config.zoom, ptz_chunks[2]),
+    ]);
 
-    // Pan
-    f.render_widget(
-        Paragraph::new(format!("Pan: {}", app.get_pan()))
+    // Degree readouts after the raw value, where the axis has `units_per_degree`
+    let position_text = |axis: Axis, label: &str, value: i32| match degree_label(
+        axis,
+        value,
+        config.control(axis),
+        &config.display,
+    ) {
+        Some(degrees) => format!("{}: {} ({})", label, value, degrees),
+        None => format!("{}: {}", label, value),
+    };
+
+    // Pan, with an arrow lit while the control value is rising (→) or falling (←)
+    let arrow_style = Style::default()
+        .fg(theme.title)
+        .add_modifier(Modifier::BOLD);
+    if config.pan.supported {
+        let arrow = match app.motion_direction(Axis::Pan) {
+            1 => " →",
+            -1 => " ←",
+            _ => "",
+        };
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::raw(position_text(Axis::Pan, "Pan", app.get_pan())),
+                Span::styled(arrow, arrow_style),
+            ]))
             .block(Block::default().borders(Borders::ALL).title("Pan")),
-        ptz_chunks[0],
-    );
+            ptz_chunks[0],
+        );
+    }
 
     // Tilt
-    f.render_widget(
-        Paragraph::new(format!("Tilt: {}", app.get_tilt()))
+    if config.tilt.supported {
+        let arrow = match app.motion_direction(Axis::Tilt) {
+            1 => " ↑",
+            -1 => " ↓",
+            _ => "",
+        };
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::raw(position_text(Axis::Tilt, "Tilt", app.get_tilt())),
+                Span::styled(arrow, arrow_style),
+            ]))
             .block(Block::default().borders(Borders::ALL).title("Tilt")),
-        ptz_chunks[1],
-    );
+            ptz_chunks[1],
+        );
+    }
 
     // Zoom (using Gauge for visual representation)
-    let zoom_config = &app.camera_controller.config.zoom;
-    let zoom_percentage = ((app.get_zoom() - zoom_config.min) as f64 / (zoom_config.max - zoom_config.min) as f64) * 100.0;
-    f.render_widget(
-        Gauge::default()
-            .block(Block::default().borders(Borders::ALL).title("Zoom"))
-            .gauge_style(Style::default().fg(Color::Magenta).bg(Color::Black))
-            .percent(zoom_percentage as u16)
-            .label(format!("{}%", app.get_zoom())),
-        ptz_chunks[2],
-    );
+    if config.zoom.supported {
+        let zoom_config = &config.zoom;
+        let zoom_title = match app.zoom_stop() {
+            Some(index) => format!("Zoom [stop {}/{}]", index + 1, zoom_config.stops.len()),
+            None => "Zoom".to_string(),
+        };
+        let mut zoom_percentage = zoom_config.percent(app.get_zoom());
+        if zoom_config.inverted_display {
+            zoom_percentage = 100.0 - zoom_percentage;
+        }
+        f.render_widget(
+            Gauge::default()
+                .block(Block::default().borders(Borders::ALL).title(zoom_title))
+                .gauge_style(Style::default().fg(theme.gauge_fg).bg(theme.gauge_bg))
+                .percent(zoom_percentage.round() as u16)
+                .label(zoom_label(
+                    zoom_percentage,
+                    app.get_zoom(),
+                    ui_config,
+                    zoom_config,
+                )),
+            ptz_chunks[2],
+        );
+    }
 
     // Movement Speed Info
-    let pan_step = app.get_zoom_adjusted_pan_step();
-    let tilt_step = app.get_zoom_adjusted_tilt_step();
-    let base_pan_step = app.camera_controller.config.pan.step;
-    let base_tilt_step = app.camera_controller.config.tilt.step;
-    
-    let speed_info = format!(
-        "Movement Speed (Zoom-Adjusted):\n\
-         Pan: {} (base: {})\n\
-         Tilt: {} (base: {})",
-        pan_step, base_pan_step, tilt_step, base_tilt_step
-    );
-    
-    f.render_widget(
-        Paragraph::new(speed_info)
-            .style(Style::default().fg(Color::Yellow))
-            .block(Block::default().borders(Borders::ALL).title("Speed Info")),
-        ptz_chunks[3],
-    );
+    if app.show_speed_info() {
+        // A held key repeats at most once per command interval, so that sets the effective speed
+        let moves_per_second = 1000.0 / app.command_interval().as_millis().max(1) as f64;
+        let speed_line = |label: &str, step: i32, config: &ControlConfig| {
+            let units_per_second = step as f64 * moves_per_second;
+            let degrees = config
+                .units_per_degree
+                .map(|units| format!(" ({:.1}°/s)", units_per_second / units))
+                .unwrap_or_default();
+            format!(
+                "{}: {}/s{} (step {}, base {})",
+                label,
+                units_per_second.round(),
+                degrees,
+                step,
+                config.step
+            )
+        };
+        let adjustment = if config.movement.zoom_adjust {
+            "zoom-adjusted"
+        } else {
+            "zoom adjustment off"
+        };
+        let mut speed_info = format!(
+            "Max speed at {:.0} moves/s ({}):",
+            moves_per_second, adjustment
+        );
+        if config.pan.supported {
+            speed_info.push('\n');
+            speed_info.push_str(&speed_line(
+                "Pan",
+                app.get_zoom_adjusted_pan_step(),
+                &config.pan,
+            ));
+        }
+        if config.tilt.supported {
+            speed_info.push('\n');
+            speed_info.push_str(&speed_line(
+                "Tilt",
+                app.get_zoom_adjusted_tilt_step(),
+                &config.tilt,
+            ));
+        }
+
+        f.render_widget(
+            Paragraph::new(speed_info)
+                .style(Style::default().fg(theme.speed_info))
+                .block(Block::default().borders(Borders::ALL).title("Speed Info")),
+            ptz_chunks[3],
+        );
+    }
+
+    // Watchdog countdown, draining toward the return home
+    if let Some((remaining, timeout)) = app.watchdog_countdown() {
+        let ratio = remaining.as_secs_f64() / timeout.as_secs_f64().max(f64::EPSILON);
+        f.render_widget(
+            LineGauge::default()
+                .label(format!("Watchdog: {} ", watchdog_label(remaining)))
+                .style(Style::default().fg(theme.speed_info))
+                .filled_style(Style::default().fg(theme.gauge_fg).bg(theme.gauge_bg))
+                .ratio(ratio.clamp(0.0, 1.0)),
+            ptz_chunks[4],
+        );
+    }
 
     // Status/Help
     f.render_widget(
         Paragraph::new(app.status_message.clone())
             .block(Block::default().borders(Borders::ALL).title("Status")),
-        ptz_chunks[4],
+        ptz_chunks[5],
     );
 
-    // Keybindings Block
+    // Keybindings Block, generated from the active bindings so remapped keys show correctly
+    let mut help = vec!["Keybindings:".to_string()];
+    help.extend(app.keybindings().help_lines());
+    if config.movement.zoom_adjust {
+        help.extend([
+            String::new(),
+            "Note: Movement speed automatically".to_string(),
+            "adjusts based on zoom level".to_string(),
+        ]);
+    }
     f.render_widget(
-        Paragraph::new(
-            "Keybindings:\n\
-             ←/→: Pan (speed varies with zoom)\n\
-             ↑/↓: Tilt (speed varies with zoom)\n\
-             Shift+↑/↓: Zoom\n\
-             v: Toggle video feed\n\
-             q: Quit\n\
-             \n\
-             Note: Movement speed automatically\n\
-             adjusts based on zoom level"
-        )
-        .block(Block::default().borders(Borders::ALL).title("Help")),
+        Paragraph::new(help.join("\n")).block(Block::default().borders(Borders::ALL).title("Help")),
         main_chunks[1],
     );
-}
\ No newline at end of file
+
+    if let Some(selected) = app.presets_panel() {
+        render_presets_panel(f, app, selected, chunks[1]);
+    }
+}
+
+/// One line per saved preset, e.g. "2. pan=3600 tilt=0 zoom=40  wide desk [system]"
+fn preset_lines(app: &App) -> Vec<String> {
+    app.bookmarks()
+        .iter()
+        .enumerate()
+        .map(|(i, preset)| {
+            let mut line = format!(
+                "{}. pan={} tilt={} zoom={}",
+                i + 1,
+                preset.pan,
+                preset.tilt,
+                preset.zoom
+            );
+            if let Some(description) = preset.description() {
+                line.push_str(&format!("  {}", description));
+            }
+            if preset.system {
+                line.push_str(" [system]");
+            }
+            line
+        })
+        .collect()
+}
+
+/// The presets list as a popup over the main area, the selected row highlighted
+fn render_presets_panel(f: &mut Frame, app: &App, selected: usize, area: Rect) {
+    let mut lines: Vec<Line> = preset_lines(app)
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| match i == selected {
+            true => Line::styled(
+                format!("> {}", text),
+                Style::default().add_modifier(Modifier::REVERSED),
+            ),
+            false => Line::raw(format!("  {}", text)),
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::raw("No presets saved yet."));
+    }
+    let width = area.width.saturating_mul(3) / 4;
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    // Keep the selected row visible in a list taller than the popup
+    let scroll = (selected as u16 + 3).saturating_sub(height);
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(lines).scroll((scroll, 0)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Presets (Enter overwrite, d delete, Esc close)"),
+        ),
+        popup,
+    );
+}
+
+/// `--plain`: labelled lines of text with no colours, borders or gauges, in reading order,
+/// for low-vision users and terminal screen readers
+fn render_plain(f: &mut Frame, app: &App) {
+    let config = &app.camera_controller.config;
+    let mut lines = vec![match app.motion_profile() {
+        Some(profile) => format!("Camera PTZ Controller, motion profile {}", profile),
+        None => "Camera PTZ Controller".to_string(),
+    }];
+    if app.arrows_zoom() {
+        lines.push("Up and down arrows zoom".to_string());
+    }
+    if app.locked() {
+        lines.push("LOCKED: camera moves are ignored".to_string());
+    }
+    lines.push(String::new());
+    for axis in Axis::ALL {
+        let label = axis.name().to_uppercase();
+        let control = config.control(axis);
+        let value = app.camera_controller.position_of(axis);
+        lines.push(if !control.supported {
+            format!("{}: not supported", label)
+        } else if axis == Axis::Zoom {
+            let multiplier = match control
+                .display
+                .as_ref()
+                .and_then(|display| display.multiplier(value))
+            {
+                Some(multiplier) => format!("{:.1} times, ", multiplier),
+                None => String::new(),
+            };
+            format!(
+                "{}: {}{:.0} percent, raw value {}",
+                label,
+                multiplier,
+                control.percent(value),
+                value
+            )
+        } else {
+            let degrees = match degree_reading(axis, value, control, &config.display) {
+                Some((degrees, Some(side))) => format!(", {} degrees {}", degrees, side),
+                Some((degrees, None)) => format!(", {} degrees", degrees),
+                None => String::new(),
+            };
+            format!(
+                "{}: {}, {:.0} percent{}",
+                label,
+                value,
+                control.percent(value),
+                degrees
+            )
+        });
+    }
+    if let Some(index) = app.zoom_stop() {
+        lines.push(format!(
+            "ZOOM STOP: {} of {}",
+            index + 1,
+            config.zoom.stops.len()
+        ));
+    }
+    if let Some((remaining, _)) = app.watchdog_countdown() {
+        lines.push(format!("WATCHDOG: {}", watchdog_label(remaining)));
+    }
+    if let Some(selected) = app.presets_panel() {
+        lines.push(String::new());
+        lines.push(format!("PRESETS, {} saved:", app.bookmarks().len()));
+        for (i, line) in preset_lines(app).into_iter().enumerate() {
+            lines.push(if i == selected {
+                format!("{}, selected", line)
+            } else {
+                line
+            });
+        }
+    }
+    lines.push(String::new());
+    lines.push(format!("STATUS: {}", app.status_message));
+    lines.push(String::new());
+    lines.push("Keys:".to_string());
+    lines.extend(app.keybindings().help_lines());
+    app.set_panel_areas([Rect::default(); 3]);
+    f.render_widget(
+        Paragraph::new(lines.join("\n")).wrap(Wrap { trim: false }),
+        f.size(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watchdog_countdown_rounds_up_to_the_second() {
+        assert_eq!(watchdog_label(Duration::from_millis(41200)), "home in 42s");
+        assert_eq!(watchdog_label(Duration::from_secs(125)), "home in 2:05");
+        assert_eq!(watchdog_label(Duration::ZERO), "home in 0s");
+    }
+
+    #[test]
+    fn frames_are_spaced_at_least_one_interval_apart() {
+        let start = Instant::now();
+        let mut limiter = FrameLimiter::new(50);
+        assert_eq!(limiter.wait(start), Duration::ZERO);
+        limiter.drawn(start);
+        assert_eq!(
+            limiter.wait(start + Duration::from_millis(5)),
+            Duration::from_millis(15)
+        );
+        assert_eq!(
+            limiter.wait(start + Duration::from_millis(25)),
+            Duration::ZERO
+        );
+
+        let mut uncapped = FrameLimiter::new(0);
+        uncapped.drawn(start);
+        assert_eq!(uncapped.wait(start), Duration::ZERO);
+    }
+
+    #[test]
+    fn plain_mode_renders_labelled_text() {
+        use ratatui::{backend::TestBackend, Terminal};
+        use std::sync::Arc;
+
+        let mut app = App::with_paths(
+            crate::camera::CameraConfig::default(),
+            Arc::new(crate::fake::FakeCamera::new()),
+            crate::app::AppPaths::scratch(),
+        );
+        app.plain = true;
+        let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+        terminal.draw(|f| render(f, &app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer.get(x, y).symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            rows[2..5],
+            [
+                "PAN: 0, 50 percent",
+                "TILT: 0, 50 percent",
+                "ZOOM: 0 percent, raw value 0"
+            ]
+        );
+        assert!(buffer
+            .content
+            .iter()
+            .all(|cell| cell.fg == ratatui::style::Color::Reset));
+    }
+
+    #[test]
+    fn degree_label_rounds_and_names_the_side() {
+        let control = ControlConfig {
+            units_per_degree: Some(3600.0),
+            ..ControlConfig::default()
+        };
+        let mut display = DisplayConfig::default();
+        assert_eq!(
+            degree_label(Axis::Pan, -45000, &control, &display).as_deref(),
+            Some("-12.5°")
+        );
+        display.degree_precision = 9;
+        assert_eq!(
+            degree_label(Axis::Pan, 3601, &control, &display).as_deref(),
+            Some("1.000°")
+        );
+        display.degree_direction = true;
+        assert_eq!(
+            degree_label(Axis::Pan, -45000, &control, &display).as_deref(),
+            Some("12.500° L")
+        );
+        assert_eq!(
+            degree_label(Axis::Tilt, -10800, &control, &display).as_deref(),
+            Some("3.000° D")
+        );
+        display.degree_precision = 0;
+        assert_eq!(
+            degree_label(Axis::Tilt, 1000, &control, &display).as_deref(),
+            Some("0°")
+        );
+        assert_eq!(
+            degree_label(Axis::Pan, 1000, &ControlConfig::default(), &display),
+            None
+        );
+    }
+
+    #[test]
+    fn zoom_label_shows_percentage_and_optional_raw_value() {
+        let mut ui = UiConfig::default();
+        let mut zoom = ControlConfig::default();
+        assert_eq!(zoom_label(73.4, 50, &ui, &zoom), "73%");
+        ui.show_raw_zoom = true;
+        assert_eq!(zoom_label(73.4, 50, &ui, &zoom), "73% (50)");
+        zoom.display = Some(crate::camera::ZoomDisplay {
+            table: vec![(0, 1.0), (100, 5.0)],
+        });
+        assert_eq!(zoom_label(33.0, 33, &ui, &zoom), "2.3x (33)");
+        ui.show_raw_zoom = false;
+        assert_eq!(zoom_label(33.0, 33, &ui, &zoom), "2.3x");
+    }
+
+    #[test]
+    fn every_layout_renders_at_any_size() {
+        use ratatui::{backend::TestBackend, Terminal};
+        use std::sync::Arc;
+
+        for layout in [
+            PanelLayout::Horizontal,
+            PanelLayout::Vertical,
+            PanelLayout::Auto,
+        ] {
+            let mut config = crate::camera::CameraConfig::default();
+            config.ui.layout = layout;
+            let app = App::with_paths(config, Arc::new(crate::fake::FakeCamera::new()), crate::app::AppPaths::scratch());
+            for (width, height) in [(1, 1), (10, 4), (40, 50), (80, 24), (200, 60)] {
+                let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+                terminal.draw(|f| render(f, &app)).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn scroll_wheel_moves_the_panel_under_the_cursor() {
+        use crate::app::InputEvent;
+        use crossterm::event::{KeyModifiers, MouseEvent, MouseEventKind};
+        use ratatui::{backend::TestBackend, Terminal};
+        use std::sync::Arc;
+
+        let camera = crate::fake::FakeCamera::new();
+        let mut app = App::with_paths(
+            crate::camera::CameraConfig::default(),
+            Arc::new(camera.clone()),
+            crate::app::AppPaths::scratch(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| render(f, &app)).unwrap();
+        let scroll = |kind, row| {
+            InputEvent::Mouse(MouseEvent {
+                kind,
+                column: 5,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+
+        // Title is rows 0-2, then Pan, Tilt and Zoom three rows each
+        std::thread::sleep(app.command_interval());
+        app.update(scroll(MouseEventKind::ScrollUp, 10));
+        assert_eq!(camera.value("zoom_absolute"), Some(10));
+        std::thread::sleep(app.command_interval());
+        app.update(scroll(MouseEventKind::ScrollDown, 7));
+        assert_eq!(camera.value("tilt_absolute"), Some(-27300));
+        let before = camera.commands().len();
+        app.update(scroll(MouseEventKind::ScrollUp, 20));
+        assert_eq!(camera.commands().len(), before);
+    }
+
+    #[test]
+    fn clicking_the_pan_tilt_panels_aims_there_in_one_command() {
+        use crate::app::InputEvent;
+        use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+        use ratatui::{backend::TestBackend, Terminal};
+        use std::sync::Arc;
+
+        let camera = crate::fake::FakeCamera::new();
+        let mut app = App::with_paths(
+            crate::camera::CameraConfig::default(),
+            Arc::new(camera.clone()),
+            crate::app::AppPaths::scratch(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| render(f, &app)).unwrap();
+        let click = |column, row| {
+            InputEvent::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        let position = || ["pan_absolute", "tilt_absolute"].map(|name| camera.value(name).unwrap());
+
+        // Pan and Tilt span rows 3-8 of the left half: top right is both maxima
+        let before = camera.commands().len();
+        app.update(click(39, 3));
+        assert_eq!(position(), [468000, 324000]);
+        assert_eq!(camera.commands().len(), before + 1);
+        app.update(click(0, 8));
+        assert_eq!(position(), [-468000, -324000]);
+        // Clicks on the Zoom panel or elsewhere don't aim
+        app.update(click(20, 10));
+        app.update(click(60, 5));
+        assert_eq!(position(), [-468000, -324000]);
+        assert_eq!(camera.value("zoom_absolute"), Some(0));
+    }
+
+    #[test]
+    fn auto_layout_stacks_on_tall_terminals() {
+        assert_eq!(
+            PanelLayout::Auto.direction(Rect::new(0, 0, 80, 24)),
+            Direction::Horizontal
+        );
+        assert_eq!(
+            PanelLayout::Auto.direction(Rect::new(0, 0, 60, 40)),
+            Direction::Vertical
+        );
+        assert_eq!(
+            PanelLayout::Horizontal.direction(Rect::new(0, 0, 60, 40)),
+            Direction::Horizontal
+        );
+    }
+
+    #[test]
+    fn theme_colours_parse_from_config() {
+        let ui: UiConfig =
+            toml::from_str("[theme]\ngauge_fg = \"#ff8800\"\ngauge_bg = \"blue\"").unwrap();
+        assert_eq!(
+            ui.theme.gauge_fg,
+            ratatui::style::Color::Rgb(0xff, 0x88, 0x00)
+        );
+        assert_eq!(ui.theme.gauge_bg, ratatui::style::Color::Blue);
+        assert_eq!(ui.theme.title, Theme::default().title);
+        assert!(toml::from_str::<UiConfig>("[theme]\ntitle = \"notacolour\"").is_err());
+    }
+}
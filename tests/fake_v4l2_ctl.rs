@@ -0,0 +1,136 @@
+//! Runs the real binary against a fake `v4l2-ctl` shell script on `PATH`, which records
+//! its arguments and answers `--get-ctrl` with canned values.
+#![cfg(unix)]
+
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+const FAKE_V4L2_CTL: &str = r#"#!/bin/sh
+echo "$*" >> "$FAKE_V4L2_LOG"
+case "$*" in
+    --version) echo "v4l2-ctl 1.22.1" ;;
+    *--get-ctrl*)
+        echo "pan_absolute: 3600"
+        echo "tilt_absolute: -7200"
+        echo "zoom_absolute: 40"
+        ;;
+esac
+"#;
+
+const CONFIG: &str = r#"
+device = "/dev/fake0"
+
+[pan]
+min = -468000
+max = 468000
+step = 3600
+
+[tilt]
+min = -324000
+max = 324000
+step = 3600
+
+[zoom]
+min = 0
+max = 100
+step = 10
+"#;
+
+/// A temp working directory with `config.toml` and the fake script in `bin/`
+struct Sandbox {
+    dir: PathBuf,
+}
+
+impl Sandbox {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("tiny-ptz-{}-{}", name, std::process::id()));
+        let bin = dir.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(dir.join("config.toml"), CONFIG).unwrap();
+        let script = bin.join("v4l2-ctl");
+        fs::write(&script, FAKE_V4L2_CTL).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        Sandbox { dir }
+    }
+
+    fn log(&self) -> PathBuf {
+        self.dir.join("v4l2-ctl.log")
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let path = format!("{}:{}", self.dir.join("bin").display(), std::env::var("PATH").unwrap_or_default());
+        let mut command = Command::new(env!("CARGO_BIN_EXE_tiny-ptz"));
+        command.args(args).current_dir(&self.dir).env("PATH", path).env("FAKE_V4L2_LOG", self.log());
+        command
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        self.command(args).output().unwrap()
+    }
+
+    /// Runs with `input` piped to stdin
+    fn run_with_stdin(&self, args: &[&str], input: &str) -> Output {
+        let mut child = self.command(args).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+        child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    /// Recorded invocations, ignoring the `--version` probe
+    fn calls(&self) -> Vec<String> {
+        read_lines(&self.log()).into_iter().filter(|line| line != "--version").collect()
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn read_lines(path: &Path) -> Vec<String> {
+    fs::read_to_string(path).unwrap_or_default().lines().map(str::to_string).collect()
+}
+
+#[test]
+fn get_reads_all_axes_in_one_call() {
+    let sandbox = Sandbox::new("get");
+    let output = sandbox.run(&["get"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "pan=3600 tilt=-7200 zoom=40\n");
+    assert_eq!(sandbox.calls(), ["-d /dev/fake0 --get-ctrl pan_absolute,tilt_absolute,zoom_absolute"]);
+}
+
+#[test]
+fn set_sends_only_the_named_axes_clamped() {
+    let sandbox = Sandbox::new("set");
+    let output = sandbox.run(&["set", "pan=7200", "zoom=150"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "pan=7200 zoom=100\n");
+    assert_eq!(
+        sandbox.calls(),
+        [
+            "-d /dev/fake0 --get-ctrl pan_absolute,tilt_absolute,zoom_absolute",
+            "-d /dev/fake0 --set-ctrl pan_absolute=7200,zoom_absolute=100",
+        ]
+    );
+}
+
+#[test]
+fn config_can_be_piped_on_stdin() {
+    let sandbox = Sandbox::new("stdin-config");
+    fs::remove_file(sandbox.dir.join("config.toml")).unwrap();
+    let piped = CONFIG.replace("/dev/fake0", "/dev/piped0");
+    let output = sandbox.run_with_stdin(&["--config", "-", "get"], &piped);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "pan=3600 tilt=-7200 zoom=40\n");
+    assert_eq!(sandbox.calls(), ["-d /dev/piped0 --get-ctrl pan_absolute,tilt_absolute,zoom_absolute"]);
+
+    let output = sandbox.run_with_stdin(&["--config", "-", "get"], "  \n");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("the config on stdin is empty"));
+    let output = sandbox.run_with_stdin(&["--config", "-", "get"], "device = ");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to parse the config on stdin"));
+}